@@ -5,10 +5,11 @@
 //! Based on: gnucash/bindings/python/example_scripts/simple_business_create.py
 
 use gnucash_ext::{
-    init_engine, Book, Customer, Employee, Entry, GNCAccountType, Invoice, Job, Numeric, Vendor,
+    init_engine, Book, Customer, Employee, Entry, GNCAccountType, Invoice, Job, Numeric, Result,
+    Time64, Vendor,
 };
 
-fn main() {
+fn main() -> Result<()> {
     init_engine();
 
     println!("Creating business entities...\n");
@@ -18,28 +19,28 @@ fn main() {
     // Create a root account (needed for posting invoices)
     let root = gnucash_ext::Account::new(&book);
     root.begin_edit();
-    root.set_name("Root");
-    root.set_type(GNCAccountType::ACCT_TYPE_ROOT);
+    root.set_name("Root")?;
+    root.set_type(GNCAccountType::ACCT_TYPE_ROOT)?;
     root.commit_edit();
-    book.set_root_account(&root);
+    book.set_root_account(&root)?;
 
     // Create a customer
     println!("Creating customer...");
     let customer = Customer::new(&book);
     customer.begin_edit();
-    customer.set_id("CUST001");
-    customer.set_name("Acme Corporation");
-    customer.set_notes("Our best customer");
-    customer.set_active(true);
+    customer.set_id("CUST001")?;
+    customer.set_name("Acme Corporation")?;
+    customer.set_notes("Our best customer")?;
+    customer.set_active(true)?;
 
     // Set customer address
     if let Some(addr) = customer.addr() {
-        addr.set_name("Acme Corporation");
-        addr.set_addr1("123 Main Street");
-        addr.set_addr2("Suite 100");
-        addr.set_addr3("Anytown, ST 12345");
-        addr.set_phone("555-1234");
-        addr.set_email("billing@acme.com");
+        addr.set_name("Acme Corporation")?;
+        addr.set_addr1("123 Main Street")?;
+        addr.set_addr2("Suite 100")?;
+        addr.set_addr3("Anytown, ST 12345")?;
+        addr.set_phone("555-1234")?;
+        addr.set_email("billing@acme.com")?;
     }
 
     customer.commit_edit();
@@ -49,15 +50,15 @@ fn main() {
     println!("\nCreating vendor...");
     let vendor = Vendor::new(&book);
     vendor.begin_edit();
-    vendor.set_id("VEND001");
-    vendor.set_name("Office Supplies Inc");
-    vendor.set_notes("Office supply vendor");
-    vendor.set_active(true);
+    vendor.set_id("VEND001")?;
+    vendor.set_name("Office Supplies Inc")?;
+    vendor.set_notes("Office supply vendor")?;
+    vendor.set_active(true)?;
 
     if let Some(addr) = vendor.addr() {
-        addr.set_name("Office Supplies Inc");
-        addr.set_addr1("456 Commerce Blvd");
-        addr.set_addr3("Business City, ST 54321");
+        addr.set_name("Office Supplies Inc")?;
+        addr.set_addr1("456 Commerce Blvd")?;
+        addr.set_addr3("Business City, ST 54321")?;
     }
 
     vendor.commit_edit();
@@ -67,15 +68,15 @@ fn main() {
     println!("\nCreating employee...");
     let employee = Employee::new(&book);
     employee.begin_edit();
-    employee.set_id("EMP001");
-    employee.set_username("jsmith");
-    employee.set_active(true);
-    employee.set_workday(Numeric::new(8, 1)); // 8 hours
-    employee.set_rate(Numeric::new(5000, 100)); // $50.00/hour
+    employee.set_id("EMP001")?;
+    employee.set_username("jsmith")?;
+    employee.set_active(true)?;
+    employee.set_workday(Numeric::new(8, 1))?; // 8 hours
+    employee.set_rate(Numeric::new(5000, 100))?; // $50.00/hour
 
     if let Some(addr) = employee.addr() {
-        addr.set_name("John Smith");
-        addr.set_addr1("789 Employee Lane");
+        addr.set_name("John Smith")?;
+        addr.set_addr1("789 Employee Lane")?;
     }
 
     employee.commit_edit();
@@ -89,14 +90,14 @@ fn main() {
     println!("\nCreating job...");
     let job = Job::new(&book);
     job.begin_edit();
-    job.set_id("JOB001");
-    job.set_name("Website Redesign");
-    job.set_reference("Project #2024-001");
-    job.set_active(true);
+    job.set_id("JOB001")?;
+    job.set_name("Website Redesign")?;
+    job.set_reference("Project #2024-001")?;
+    job.set_active(true)?;
 
     // Link job to customer
     let customer_owner = customer.to_owner();
-    job.set_owner(&customer_owner);
+    job.set_owner(&customer_owner)?;
 
     job.commit_edit();
     println!("  Job: {} ({})", job.name().unwrap(), job.id().unwrap());
@@ -105,11 +106,11 @@ fn main() {
     println!("\nCreating invoice...");
     let invoice = Invoice::new(&book);
     invoice.begin_edit();
-    invoice.set_id("INV-001");
-    invoice.set_notes("Invoice for consulting services");
+    invoice.set_id("INV-001")?;
+    invoice.set_notes("Invoice for consulting services")?;
 
     // Set the invoice owner to the customer
-    invoice.set_owner(&customer_owner);
+    invoice.set_owner(&customer_owner)?;
 
     invoice.commit_edit();
 
@@ -117,18 +118,22 @@ fn main() {
     println!("Adding invoice entries...");
 
     let entry1 = Entry::new(&book);
-    entry1.set_date(1704067200); // Jan 1, 2024
-    entry1.set_description("Consulting - Day 1");
-    entry1.set_quantity(Numeric::new(8, 1)); // 8 hours
-    entry1.set_inv_price(Numeric::new(15000, 100)); // $150/hour
+    entry1.begin_edit();
+    entry1.set_date(Time64::from_secs(1704067200))?; // Jan 1, 2024
+    entry1.set_description("Consulting - Day 1")?;
+    entry1.set_quantity(Numeric::new(8, 1))?; // 8 hours
+    entry1.set_inv_price(Numeric::new(15000, 100))?; // $150/hour
+    entry1.commit_edit();
     invoice.add_entry(&entry1);
     println!("  Entry 1: Consulting - Day 1 (8 hrs @ $150)");
 
     let entry2 = Entry::new(&book);
-    entry2.set_date(1704153600); // Jan 2, 2024
-    entry2.set_description("Consulting - Day 2");
-    entry2.set_quantity(Numeric::new(6, 1)); // 6 hours
-    entry2.set_inv_price(Numeric::new(15000, 100)); // $150/hour
+    entry2.begin_edit();
+    entry2.set_date(Time64::from_secs(1704153600))?; // Jan 2, 2024
+    entry2.set_description("Consulting - Day 2")?;
+    entry2.set_quantity(Numeric::new(6, 1))?; // 6 hours
+    entry2.set_inv_price(Numeric::new(15000, 100))?; // $150/hour
+    entry2.commit_edit();
     invoice.add_entry(&entry2);
     println!("  Entry 2: Consulting - Day 2 (6 hrs @ $150)");
 
@@ -157,4 +162,5 @@ fn main() {
     std::mem::forget(vendor);
     std::mem::forget(employee);
     std::mem::forget(root);
+    Ok(())
 }
@@ -2,37 +2,63 @@
 //!
 //! This module provides fluent builders for complex entity creation.
 
-use gnucash_sys::{Account, Book, Numeric, Split, Transaction};
+use gnucash_sys::{
+    Account, Book, Commodity, Editable, Error, GNCAccountType, GncDate, Numeric, RoundingMode,
+    Split, Time64, Transaction, DENOM_AUTO,
+};
 
 /// Builder for creating transactions with splits.
 ///
+/// Split values are denominated in the transaction currency and must sum to
+/// zero. Splits in accounts with a different commodity also need an amount
+/// in that commodity, given directly or through an exchange rate.
+///
 /// # Example
 /// ```ignore
-/// use gnucash_sys::{TransactionBuilder, Numeric};
+/// use gnucash_sys::{GncDate, TransactionBuilder, Numeric};
 ///
 /// let txn = TransactionBuilder::new(&book)
 ///     .description("Groceries")
-///     .date(2024, 1, 15)
+///     .date(GncDate::from_dmy(15, 1, 2024)?)
+///     .currency("USD")
 ///     .split(&checking, Numeric::new(-5000, 100), None)  // -$50.00
 ///     .split(&expenses, Numeric::new(5000, 100), None)   // $50.00
 ///     .build()?;
+///
+/// // Buying EUR 45.00 with USD 50.00
+/// let fx = TransactionBuilder::new(&book)
+///     .currency("USD")
+///     .split(&usd_checking, Numeric::new(-5000, 100), None)
+///     .split_with_amount(&eur_checking, Numeric::new(5000, 100), Numeric::new(4500, 100), None)
+///     .build()?;
 /// ```
 pub struct TransactionBuilder<'a> {
     book: &'a Book,
     description: Option<String>,
     num: Option<String>,
     notes: Option<String>,
-    date_posted: Option<(i32, i32, i32)>, // (day, month, year)
+    date_posted: Option<GncDate>,
     currency_mnemonic: Option<String>,
     splits: Vec<SplitSpec<'a>>,
 }
 
 struct SplitSpec<'a> {
-    account: &'a Account,
-    amount: Numeric,
+    account: &'a Account<'a>,
+    value: Numeric,
+    quantity: SplitQuantity,
     memo: Option<String>,
 }
 
+/// How a split's amount (in the account commodity) is derived from its value.
+enum SplitQuantity {
+    /// Amount equals value; only valid when the account is in the transaction currency.
+    SameAsValue,
+    /// Explicit amount in the account commodity.
+    Amount(Numeric),
+    /// Account commodity units per unit of transaction currency.
+    Rate(Numeric),
+}
+
 impl<'a> TransactionBuilder<'a> {
     /// Creates a new TransactionBuilder.
     pub fn new(book: &'a Book) -> Self {
@@ -65,23 +91,67 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
-    /// Sets the date posted (day, month, year).
-    pub fn date(mut self, day: i32, month: i32, year: i32) -> Self {
-        self.date_posted = Some((day, month, year));
+    /// Sets the date posted.
+    pub fn date(mut self, date: GncDate) -> Self {
+        self.date_posted = Some(date);
         self
     }
 
-    /// Sets the currency mnemonic (e.g., "USD").
+    /// Sets the transaction currency by ISO code (e.g., "USD").
+    ///
+    /// If not set, the currency of the first split's account (or its
+    /// nearest ancestor with a currency) is used.
     pub fn currency(mut self, mnemonic: &str) -> Self {
         self.currency_mnemonic = Some(mnemonic.to_string());
         self
     }
 
-    /// Adds a split to the transaction.
-    pub fn split(mut self, account: &'a Account, amount: Numeric, memo: Option<&str>) -> Self {
+    /// Adds a split whose amount equals its value.
+    ///
+    /// The account must be denominated in the transaction currency.
+    pub fn split(self, account: &'a Account<'a>, value: Numeric, memo: Option<&str>) -> Self {
+        self.push_split(account, value, SplitQuantity::SameAsValue, memo)
+    }
+
+    /// Adds a split with an explicit amount in the account's commodity.
+    ///
+    /// `value` is in the transaction currency.
+    pub fn split_with_amount(
+        self,
+        account: &'a Account<'a>,
+        value: Numeric,
+        amount: Numeric,
+        memo: Option<&str>,
+    ) -> Self {
+        self.push_split(account, value, SplitQuantity::Amount(amount), memo)
+    }
+
+    /// Adds a split whose amount is `value * rate`, rounded to the
+    /// account's smallest commodity unit.
+    ///
+    /// `rate` is the number of account commodity units per unit of
+    /// transaction currency.
+    pub fn split_with_rate(
+        self,
+        account: &'a Account<'a>,
+        value: Numeric,
+        rate: Numeric,
+        memo: Option<&str>,
+    ) -> Self {
+        self.push_split(account, value, SplitQuantity::Rate(rate), memo)
+    }
+
+    fn push_split(
+        mut self,
+        account: &'a Account<'a>,
+        value: Numeric,
+        quantity: SplitQuantity,
+        memo: Option<&str>,
+    ) -> Self {
         self.splits.push(SplitSpec {
             account,
-            amount,
+            value,
+            quantity,
             memo: memo.map(|s| s.to_string()),
         });
         self
@@ -90,8 +160,8 @@ impl<'a> TransactionBuilder<'a> {
     /// Adds a transfer (two splits: debit and credit).
     pub fn transfer(
         self,
-        from: &'a Account,
-        to: &'a Account,
+        from: &'a Account<'a>,
+        to: &'a Account<'a>,
         amount: Numeric,
         memo: Option<&str>,
     ) -> Self {
@@ -101,84 +171,102 @@ impl<'a> TransactionBuilder<'a> {
 
     /// Builds and returns the transaction.
     ///
-    /// Returns an error if the transaction is imbalanced or invalid.
-    pub fn build(self) -> gnucash_sys::Result<Transaction> {
+    /// Returns an error if the currency cannot be resolved, a split in a
+    /// foreign commodity has no amount, or the values do not balance.
+    pub fn build(self) -> gnucash_sys::Result<Transaction<'a>> {
         if self.splits.is_empty() {
-            return Err(gnucash_sys::Error::InvalidOperation(
+            return Err(Error::InvalidOperation(
                 "Transaction must have at least one split".to_string(),
             ));
         }
 
-        // Check balance
-        let mut total_num: i64 = 0;
-        let mut common_denom: i64 = 1;
-        for split in &self.splits {
-            // Simple balance check (assumes same currency)
-            let amount = split.amount;
-            // Find common denominator
-            common_denom = lcm(common_denom, amount.denom());
-        }
+        let currency = self.resolve_currency()?;
+
+        // Resolve each split's amount and check the values balance in the
+        // transaction currency
+        let mut amounts = Vec::with_capacity(self.splits.len());
+        let mut total = Numeric::zero();
         for split in &self.splits {
-            let amount = split.amount;
-            let scaled = amount.num() * (common_denom / amount.denom());
-            total_num += scaled;
+            amounts.push(split_amount(split, &currency)?);
+            total = total.checked_add(split.value, DENOM_AUTO, RoundingMode::Never)?;
         }
-        if total_num != 0 {
-            return Err(gnucash_sys::Error::InvalidOperation(format!(
-                "Transaction is imbalanced by {}/{}",
-                total_num, common_denom
-            )));
+        if !total.is_zero() {
+            return Err(Error::Unbalanced);
         }
 
-        // Create transaction
+        // Create transaction; the edit is rolled back if any setter fails
         let txn = Transaction::new(self.book);
-        txn.begin_edit();
+        txn.edit_with(|txn| {
+            txn.set_currency(&currency)?;
 
-        if let Some(desc) = &self.description {
-            txn.set_description(desc);
-        }
-        if let Some(num) = &self.num {
-            txn.set_num(num);
-        }
-        if let Some(notes) = &self.notes {
-            txn.set_notes(notes);
-        }
-        if let Some((day, month, year)) = self.date_posted {
-            txn.set_date(day, month, year);
-        }
-
-        // Create splits
-        for split_spec in self.splits {
-            let split = Split::new(self.book);
-            split.set_account(split_spec.account);
-            split.set_transaction(&txn);
-            split.set_amount(split_spec.amount);
-            split.set_value(split_spec.amount);
-            if let Some(memo) = &split_spec.memo {
-                split.set_memo(memo);
+            if let Some(desc) = &self.description {
+                txn.set_description(desc)?;
+            }
+            if let Some(num) = &self.num {
+                txn.set_num(num)?;
+            }
+            if let Some(notes) = &self.notes {
+                txn.set_notes(notes)?;
+            }
+            if let Some(date) = self.date_posted {
+                txn.set_date(date)?;
             }
-        }
 
-        txn.commit_edit();
+            // Create splits
+            for (split_spec, amount) in self.splits.into_iter().zip(amounts) {
+                let mut split = Split::new(self.book);
+                split.set_account(split_spec.account)?;
+                split.set_transaction(txn)?;
+                split.set_amount(amount)?;
+                split.set_value(split_spec.value)?;
+                if let Some(memo) = &split_spec.memo {
+                    split.set_memo(memo)?;
+                }
+                // The transaction owns the split now
+                split.mark_unowned();
+            }
+            Ok::<_, Error>(())
+        })?;
         Ok(txn)
     }
-}
 
-/// Calculates the least common multiple.
-fn lcm(a: i64, b: i64) -> i64 {
-    (a * b).abs() / gcd(a, b)
+    /// Looks up the configured currency, or falls back to the first split's account.
+    fn resolve_currency(&self) -> gnucash_sys::Result<Commodity<'a>> {
+        match &self.currency_mnemonic {
+            Some(mnemonic) => self
+                .book
+                .commodity_table()
+                .and_then(|table| table.lookup_currency(mnemonic))
+                .ok_or_else(|| Error::InvalidOperation(format!("Unknown currency: {}", mnemonic))),
+            None => self.splits[0].account.currency_or_parent().ok_or_else(|| {
+                Error::InvalidOperation(
+                    "Transaction currency not set and first account has no currency".to_string(),
+                )
+            }),
+        }
+    }
 }
 
-/// Calculates the greatest common divisor.
-fn gcd(mut a: i64, mut b: i64) -> i64 {
-    a = a.abs();
-    b = b.abs();
-    while b != 0 {
-        let t = b;
-        b = a % b;
-        a = t;
+/// Returns the amount (in the account commodity) for a split.
+fn split_amount(split: &SplitSpec<'_>, currency: &Commodity) -> gnucash_sys::Result<Numeric> {
+    match split.quantity {
+        SplitQuantity::Amount(amount) => Ok(amount),
+        SplitQuantity::Rate(rate) => split.value.checked_mul(
+            rate,
+            i64::from(split.account.commodity_scu()),
+            RoundingMode::RoundHalfUp,
+        ),
+        SplitQuantity::SameAsValue => match split.account.commodity() {
+            Some(commodity) if commodity != *currency => Err(Error::InvalidOperation(format!(
+                "Account {} is in {} but the transaction is in {}; \
+                 use split_with_amount or split_with_rate",
+                split.account.name().unwrap_or_default(),
+                commodity.mnemonic().unwrap_or_default(),
+                currency.mnemonic().unwrap_or_default(),
+            ))),
+            _ => Ok(split.value),
+        },
     }
-    a
 }
 
 use crate::business::{Entry, Invoice, Owner};
@@ -192,7 +280,7 @@ use crate::business::{Entry, Invoice, Owner};
 /// let invoice = InvoiceBuilder::new(&book)
 ///     .id("INV-001")
 ///     .owner(&customer.to_owner())
-///     .date_opened(time64)
+///     .date_opened(Time64::from_dmy(15, 1, 2024)?)
 ///     .entry("Consulting", Numeric::new(10000, 100), Numeric::new(1, 1), &income_account)
 ///     .build()?;
 /// ```
@@ -201,8 +289,8 @@ pub struct InvoiceBuilder<'a> {
     id: Option<String>,
     notes: Option<String>,
     billing_id: Option<String>,
-    owner: Option<&'a Owner>,
-    date_opened: Option<i64>,
+    owner: Option<&'a Owner<'a>>,
+    date_opened: Option<Time64>,
     entries: Vec<EntrySpec<'a>>,
 }
 
@@ -210,7 +298,7 @@ struct EntrySpec<'a> {
     description: String,
     price: Numeric,
     quantity: Numeric,
-    account: &'a Account,
+    account: &'a Account<'a>,
     action: Option<String>,
 }
 
@@ -247,13 +335,13 @@ impl<'a> InvoiceBuilder<'a> {
     }
 
     /// Sets the owner (customer, vendor, etc.).
-    pub fn owner(mut self, owner: &'a Owner) -> Self {
+    pub fn owner(mut self, owner: &'a Owner<'a>) -> Self {
         self.owner = Some(owner);
         self
     }
 
     /// Sets the date opened.
-    pub fn date_opened(mut self, date: i64) -> Self {
+    pub fn date_opened(mut self, date: Time64) -> Self {
         self.date_opened = Some(date);
         self
     }
@@ -264,7 +352,7 @@ impl<'a> InvoiceBuilder<'a> {
         description: &str,
         price: Numeric,
         quantity: Numeric,
-        account: &'a Account,
+        account: &'a Account<'a>,
     ) -> Self {
         self.entries.push(EntrySpec {
             description: description.to_string(),
@@ -282,7 +370,7 @@ impl<'a> InvoiceBuilder<'a> {
         description: &str,
         price: Numeric,
         quantity: Numeric,
-        account: &'a Account,
+        account: &'a Account<'a>,
         action: &str,
     ) -> Self {
         self.entries.push(EntrySpec {
@@ -296,61 +384,270 @@ impl<'a> InvoiceBuilder<'a> {
     }
 
     /// Builds and returns the invoice.
-    pub fn build(self) -> gnucash_sys::Result<Invoice> {
+    pub fn build(self) -> gnucash_sys::Result<Invoice<'a>> {
         let invoice = Invoice::new(self.book);
-        invoice.begin_edit();
+        invoice.edit_with(|invoice| {
+            if let Some(id) = &self.id {
+                invoice.set_id(id)?;
+            }
+            if let Some(notes) = &self.notes {
+                invoice.set_notes(notes)?;
+            }
+            if let Some(billing_id) = &self.billing_id {
+                invoice.set_billing_id(billing_id)?;
+            }
+            if let Some(owner) = self.owner {
+                invoice.set_owner(owner)?;
+            }
+            if let Some(date) = self.date_opened {
+                invoice.set_date_opened(date)?;
+            }
 
-        if let Some(id) = &self.id {
-            invoice.set_id(id);
-        }
-        if let Some(notes) = &self.notes {
-            invoice.set_notes(notes);
-        }
-        if let Some(billing_id) = &self.billing_id {
-            invoice.set_billing_id(billing_id);
-        }
-        if let Some(owner) = self.owner {
-            invoice.set_owner(owner);
-        }
-        if let Some(date) = self.date_opened {
-            invoice.set_date_opened(date);
+            // Create entries
+            for entry_spec in &self.entries {
+                let entry = Entry::new(self.book);
+                entry.edit_with(|entry| {
+                    entry.set_description(&entry_spec.description)?;
+                    entry.set_inv_price(entry_spec.price)?;
+                    entry.set_quantity(entry_spec.quantity)?;
+                    entry.set_inv_account(entry_spec.account)?;
+                    if let Some(action) = &entry_spec.action {
+                        entry.set_action(action)?;
+                    }
+                    Ok::<_, Error>(())
+                })?;
+                // Note: Entry needs to be added to invoice via gncInvoiceAddEntry
+                // which we'd need to expose
+            }
+            Ok::<_, Error>(())
+        })?;
+        Ok(invoice)
+    }
+}
+
+/// Builder for creating an account hierarchy from full account names.
+///
+/// Each path is created with [`Book::ensure_account`]: existing accounts are
+/// reused, missing parents are created with the top-level type of the
+/// account's category, and the book's account separator is respected. The
+/// account tree owns every account it creates.
+///
+/// # Example
+/// ```ignore
+/// use gnucash_ext::{AccountTreeBuilder, GNCAccountType};
+///
+/// let accounts = AccountTreeBuilder::new(&book)
+///     .commodity(&usd)
+///     .placeholder("Assets", GNCAccountType::ACCT_TYPE_ASSET)
+///     .account("Assets:Bank:Checking", GNCAccountType::ACCT_TYPE_BANK)
+///     .account("Expenses:Food:Groceries", GNCAccountType::ACCT_TYPE_EXPENSE)
+///     .build()?;
+/// let checking = &accounts[1];
+/// ```
+pub struct AccountTreeBuilder<'a> {
+    book: &'a Book,
+    commodity: Option<&'a Commodity<'a>>,
+    accounts: Vec<AccountSpec>,
+}
+
+struct AccountSpec {
+    path: String,
+    account_type: GNCAccountType,
+    placeholder: bool,
+}
+
+impl<'a> AccountTreeBuilder<'a> {
+    /// Creates a new AccountTreeBuilder.
+    pub fn new(book: &'a Book) -> Self {
+        Self {
+            book,
+            commodity: None,
+            accounts: Vec::new(),
         }
+    }
 
-        // Create entries
-        for entry_spec in self.entries {
-            let entry = Entry::new(self.book);
-            entry.begin_edit();
-            entry.set_description(&entry_spec.description);
-            entry.set_inv_price(entry_spec.price);
-            entry.set_quantity(entry_spec.quantity);
-            entry.set_inv_account(entry_spec.account);
-            if let Some(action) = &entry_spec.action {
-                entry.set_action(action);
+    /// Sets the commodity given to accounts on the paths that have none,
+    /// such as new top-level accounts, which have no parent to inherit one
+    /// from.
+    pub fn commodity(mut self, commodity: &'a Commodity<'a>) -> Self {
+        self.commodity = Some(commodity);
+        self
+    }
+
+    /// Adds an account by full name, e.g. `"Assets:Bank:Checking"`.
+    pub fn account(mut self, path: &str, account_type: GNCAccountType) -> Self {
+        self.accounts.push(AccountSpec {
+            path: path.to_string(),
+            account_type,
+            placeholder: false,
+        });
+        self
+    }
+
+    /// Adds a placeholder account, which holds child accounts but no
+    /// transactions.
+    pub fn placeholder(mut self, path: &str, account_type: GNCAccountType) -> Self {
+        self.accounts.push(AccountSpec {
+            path: path.to_string(),
+            account_type,
+            placeholder: true,
+        });
+        self
+    }
+
+    /// Creates the accounts.
+    ///
+    /// Returns one account per [`account`](Self::account) or
+    /// [`placeholder`](Self::placeholder) call, in order. Accounts created
+    /// before an error are kept.
+    ///
+    /// # Errors
+    /// Returns the first error from [`Book::ensure_account`] or from setting
+    /// the commodity or placeholder flag.
+    pub fn build(self) -> gnucash_sys::Result<Vec<Account<'a>>> {
+        let mut accounts = Vec::with_capacity(self.accounts.len());
+        for spec in &self.accounts {
+            let account = self.book.ensure_account(&spec.path, spec.account_type)?;
+            if let Some(commodity) = self.commodity {
+                set_missing_commodities(&account, commodity)?;
             }
-            entry.commit_edit();
-            // Note: Entry needs to be added to invoice via gncInvoiceAddEntry
-            // which we'd need to expose
+            if spec.placeholder && !account.is_placeholder() {
+                account.edit_with(|a| a.set_placeholder(true))?;
+            }
+            accounts.push(account);
         }
+        Ok(accounts)
+    }
+}
 
-        invoice.commit_edit();
-        Ok(invoice)
+/// Gives `commodity` to `account` and each of its ancestors below the root
+/// that has none.
+fn set_missing_commodities(account: &Account, commodity: &Commodity) -> gnucash_sys::Result<()> {
+    let mut ancestors = Vec::new();
+    let mut parent = account.parent();
+    while let Some(p) = parent {
+        parent = p.parent();
+        // The root account, which has no parent, has no commodity
+        if parent.is_some() {
+            ancestors.push(p);
+        }
+    }
+    for a in ancestors.iter().rev().filter(|a| a.commodity().is_none()) {
+        a.edit_with(|a| a.set_commodity(commodity))?;
     }
+    if account.commodity().is_none() {
+        account.edit_with(|a| a.set_commodity(commodity))?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn account_in<'a>(book: &'a Book, currency: &Commodity) -> Account<'a> {
+        let account = Account::new(book);
+        account.begin_edit();
+        account.set_commodity(currency).unwrap();
+        account.commit_edit();
+        account
+    }
+
+    #[test]
+    fn test_build_rejects_unbalanced() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let usd = table.lookup_currency("USD").unwrap();
+        let a = account_in(&book, &usd);
+        let b = account_in(&book, &usd);
+
+        let result = TransactionBuilder::new(&book)
+            .currency("USD")
+            .split(&a, Numeric::new(-5000, 100), None)
+            .split(&b, Numeric::new(4900, 100), None)
+            .build();
+        assert_eq!(result.err(), Some(Error::Unbalanced));
+    }
+
+    #[test]
+    fn test_build_unknown_currency() {
+        let book = Book::new();
+        let a = Account::new(&book);
+        let result = TransactionBuilder::new(&book)
+            .currency("XYZ")
+            .transfer(&a, &a, Numeric::new(100, 100), None)
+            .build();
+        assert!(matches!(result, Err(Error::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn test_build_foreign_split_needs_amount() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let usd = table.lookup_currency("USD").unwrap();
+        let eur = table.lookup_currency("EUR").unwrap();
+        let usd_acct = account_in(&book, &usd);
+        let eur_acct = account_in(&book, &eur);
+
+        let result = TransactionBuilder::new(&book)
+            .currency("USD")
+            .transfer(&usd_acct, &eur_acct, Numeric::new(5000, 100), None)
+            .build();
+        assert!(matches!(result, Err(Error::InvalidOperation(_))));
+
+        let txn = TransactionBuilder::new(&book)
+            .currency("USD")
+            .split(&usd_acct, Numeric::new(-5000, 100), None)
+            .split_with_rate(&eur_acct, Numeric::new(5000, 100), Numeric::new(9, 10), None)
+            .build()
+            .unwrap();
+        assert_eq!(txn.currency(), Some(usd));
+        assert_eq!(txn.split_count(), 2);
+        let eur_split = txn
+            .splits()
+            .find(|s| s.account().as_ref() == Some(&eur_acct))
+            .unwrap();
+        assert!(eur_split.amount().equal(Numeric::new(4500, 100)));
+        assert!(eur_split.value().equal(Numeric::new(5000, 100)));
+    }
+
     #[test]
-    fn test_gcd() {
-        assert_eq!(gcd(12, 8), 4);
-        assert_eq!(gcd(100, 25), 25);
-        assert_eq!(gcd(17, 13), 1);
+    fn test_build_rejects_null_byte() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let usd = table.lookup_currency("USD").unwrap();
+        let a = account_in(&book, &usd);
+        let b = account_in(&book, &usd);
+
+        let result = TransactionBuilder::new(&book)
+            .currency("USD")
+            .description("bad\0description")
+            .transfer(&a, &b, Numeric::new(100, 100), None)
+            .build();
+        assert!(matches!(result, Err(Error::StringConversion(_))));
     }
 
     #[test]
-    fn test_lcm() {
-        assert_eq!(lcm(4, 6), 12);
-        assert_eq!(lcm(100, 100), 100);
+    fn test_account_tree_builder() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let usd = table.lookup_currency("USD").unwrap();
+
+        let accounts = AccountTreeBuilder::new(&book)
+            .commodity(&usd)
+            .placeholder("Assets", GNCAccountType::ACCT_TYPE_ASSET)
+            .account("Assets:Bank:Checking", GNCAccountType::ACCT_TYPE_BANK)
+            .account("Assets:Bank:Savings", GNCAccountType::ACCT_TYPE_BANK)
+            .build()
+            .unwrap();
+        assert_eq!(accounts.len(), 3);
+        assert!(accounts[0].is_placeholder());
+        assert_eq!(accounts[1].parent().unwrap().guid(), accounts[2].parent().unwrap().guid());
+        assert_eq!(accounts[1].commodity().unwrap().mnemonic().as_deref(), Some("USD"));
+        assert_eq!(accounts[0].commodity().unwrap().mnemonic().as_deref(), Some("USD"));
     }
 }
@@ -1,27 +1,30 @@
 //! Safe wrapper for GncAddress.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::Book;
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Book, Result};
 
 /// A mailing address.
-pub struct Address {
+pub struct Address<'book> {
     ptr: NonNull<ffi::GncAddress>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Address {}
+unsafe impl Send for Address<'_> {}
 
-impl Address {
+impl<'book> Address<'book> {
     /// Creates a new Address in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncAddressCreate(book.as_ptr(), std::ptr::null_mut()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncAddressCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -30,7 +33,7 @@ impl Address {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncAddress, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -138,52 +141,73 @@ impl Address {
 
     // ==================== Setters ====================
 
+    /// Checks that the address's book is writable.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_book_writable(self.ptr.as_ptr().cast()) }
+    }
+
     /// Sets the name.
-    pub fn set_name(&self, name: &str) {
-        let c_name = CString::new(name).unwrap();
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_name = CString::new(name)?;
         unsafe { ffi::gncAddressSetName(self.ptr.as_ptr(), c_name.as_ptr()) }
+        Ok(())
     }
 
     /// Sets address line 1.
-    pub fn set_addr1(&self, addr: &str) {
-        let c_addr = CString::new(addr).unwrap();
+    pub fn set_addr1(&self, addr: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_addr = CString::new(addr)?;
         unsafe { ffi::gncAddressSetAddr1(self.ptr.as_ptr(), c_addr.as_ptr()) }
+        Ok(())
     }
 
     /// Sets address line 2.
-    pub fn set_addr2(&self, addr: &str) {
-        let c_addr = CString::new(addr).unwrap();
+    pub fn set_addr2(&self, addr: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_addr = CString::new(addr)?;
         unsafe { ffi::gncAddressSetAddr2(self.ptr.as_ptr(), c_addr.as_ptr()) }
+        Ok(())
     }
 
     /// Sets address line 3.
-    pub fn set_addr3(&self, addr: &str) {
-        let c_addr = CString::new(addr).unwrap();
+    pub fn set_addr3(&self, addr: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_addr = CString::new(addr)?;
         unsafe { ffi::gncAddressSetAddr3(self.ptr.as_ptr(), c_addr.as_ptr()) }
+        Ok(())
     }
 
     /// Sets address line 4.
-    pub fn set_addr4(&self, addr: &str) {
-        let c_addr = CString::new(addr).unwrap();
+    pub fn set_addr4(&self, addr: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_addr = CString::new(addr)?;
         unsafe { ffi::gncAddressSetAddr4(self.ptr.as_ptr(), c_addr.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the phone number.
-    pub fn set_phone(&self, phone: &str) {
-        let c_phone = CString::new(phone).unwrap();
+    pub fn set_phone(&self, phone: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_phone = CString::new(phone)?;
         unsafe { ffi::gncAddressSetPhone(self.ptr.as_ptr(), c_phone.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the fax number.
-    pub fn set_fax(&self, fax: &str) {
-        let c_fax = CString::new(fax).unwrap();
+    pub fn set_fax(&self, fax: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_fax = CString::new(fax)?;
         unsafe { ffi::gncAddressSetFax(self.ptr.as_ptr(), c_fax.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the email address.
-    pub fn set_email(&self, email: &str) {
-        let c_email = CString::new(email).unwrap();
+    pub fn set_email(&self, email: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_email = CString::new(email)?;
         unsafe { ffi::gncAddressSetEmail(self.ptr.as_ptr(), c_email.as_ptr()) }
+        Ok(())
     }
 
     /// Clears the address (sets all fields to empty).
@@ -192,7 +216,7 @@ impl Address {
     }
 }
 
-impl std::fmt::Debug for Address {
+impl std::fmt::Debug for Address<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Address")
             .field("name", &self.name())
@@ -1,29 +1,32 @@
 //! Safe wrapper for GncBillTerm.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Book, Guid, Numeric};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Book, Editable, Guid, Numeric, Result};
 
 pub use ffi::GncBillTermType as BillTermType;
 
 /// Payment terms for invoices.
-pub struct BillTerm {
+pub struct BillTerm<'book> {
     ptr: NonNull<ffi::GncBillTerm>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for BillTerm {}
+unsafe impl Send for BillTerm<'_> {}
 
-impl BillTerm {
+impl<'book> BillTerm<'book> {
     /// Creates a new BillTerm in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncBillTermCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncBillTermCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -32,7 +35,7 @@ impl BillTerm {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncBillTerm, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -121,47 +124,66 @@ impl BillTerm {
 
     // ==================== Setters ====================
 
+    /// Checks that this bill term can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "bill term") }
+    }
+
     /// Sets the name.
-    pub fn set_name(&self, name: &str) {
-        let c_name = CString::new(name).unwrap();
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_name = CString::new(name)?;
         unsafe { ffi::gncBillTermSetName(self.ptr.as_ptr(), c_name.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the description.
-    pub fn set_description(&self, desc: &str) {
-        let c_desc = CString::new(desc).unwrap();
+    pub fn set_description(&self, desc: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_desc = CString::new(desc)?;
         unsafe { ffi::gncBillTermSetDescription(self.ptr.as_ptr(), c_desc.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the bill term type.
-    pub fn set_type(&self, term_type: BillTermType) {
+    pub fn set_type(&self, term_type: BillTermType) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncBillTermSetType(self.ptr.as_ptr(), term_type) }
+        Ok(())
     }
 
     /// Sets the due days.
-    pub fn set_due_days(&self, days: i32) {
+    pub fn set_due_days(&self, days: i32) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncBillTermSetDueDays(self.ptr.as_ptr(), days) }
+        Ok(())
     }
 
     /// Sets the discount days.
-    pub fn set_discount_days(&self, days: i32) {
+    pub fn set_discount_days(&self, days: i32) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncBillTermSetDiscountDays(self.ptr.as_ptr(), days) }
+        Ok(())
     }
 
     /// Sets the discount.
-    pub fn set_discount(&self, discount: Numeric) {
+    pub fn set_discount(&self, discount: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncBillTermSetDiscount(self.ptr.as_ptr(), discount.into()) }
+        Ok(())
     }
 
     /// Sets the cutoff day.
-    pub fn set_cutoff(&self, cutoff: i32) {
+    pub fn set_cutoff(&self, cutoff: i32) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncBillTermSetCutoff(self.ptr.as_ptr(), cutoff) }
+        Ok(())
     }
 
     // ==================== Lookup ====================
 
     /// Looks up a bill term by name.
-    pub fn lookup_by_name(book: &Book, name: &str) -> Option<Self> {
+    pub fn lookup_by_name(book: &'book Book, name: &str) -> Option<Self> {
         let c_name = CString::new(name).ok()?;
         unsafe {
             let ptr = ffi::gncBillTermLookupByName(book.as_ptr(), c_name.as_ptr());
@@ -170,7 +192,19 @@ impl BillTerm {
     }
 }
 
-impl std::fmt::Debug for BillTerm {
+/// The engine cannot roll back billing terms, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for BillTerm<'_> {
+    fn begin_edit(&self) {
+        BillTerm::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        BillTerm::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for BillTerm<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BillTerm")
             .field("name", &self.name())
@@ -1,29 +1,32 @@
 //! Safe wrapper for GncCustomer.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Book, Guid, Numeric};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Book, Editable, Guid, Numeric, Result};
 
 use super::{Address, Owner};
 
 /// A customer entity.
-pub struct Customer {
+pub struct Customer<'book> {
     ptr: NonNull<ffi::GncCustomer>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Customer {}
+unsafe impl Send for Customer<'_> {}
 
-impl Customer {
+impl<'book> Customer<'book> {
     /// Creates a new Customer in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncCustomerCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncCustomerCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -32,7 +35,7 @@ impl Customer {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncCustomer, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -64,7 +67,7 @@ impl Customer {
     }
 
     /// Creates an Owner from this customer.
-    pub fn to_owner(&self) -> Owner {
+    pub fn to_owner(&self) -> Owner<'book> {
         let mut owner = Owner::new();
         unsafe { ffi::gncOwnerInitCustomer(owner.as_mut_ptr(), self.ptr.as_ptr()) };
         owner
@@ -109,7 +112,7 @@ impl Customer {
     }
 
     /// Returns the billing address.
-    pub fn addr(&self) -> Option<Address> {
+    pub fn addr(&self) -> Option<Address<'book>> {
         unsafe {
             let ptr = ffi::gncCustomerGetAddr(self.ptr.as_ptr());
             Address::from_raw(ptr, false)
@@ -117,7 +120,7 @@ impl Customer {
     }
 
     /// Returns the shipping address.
-    pub fn ship_addr(&self) -> Option<Address> {
+    pub fn ship_addr(&self) -> Option<Address<'book>> {
         unsafe {
             let ptr = ffi::gncCustomerGetShipAddr(self.ptr.as_ptr());
             Address::from_raw(ptr, false)
@@ -141,42 +144,71 @@ impl Customer {
 
     // ==================== Setters ====================
 
+    /// Checks that this customer can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "customer") }
+    }
+
     /// Sets the customer ID.
-    pub fn set_id(&self, id: &str) {
-        let c_id = CString::new(id).unwrap();
+    pub fn set_id(&self, id: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_id = CString::new(id)?;
         unsafe { ffi::gncCustomerSetID(self.ptr.as_ptr(), c_id.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the customer name.
-    pub fn set_name(&self, name: &str) {
-        let c_name = CString::new(name).unwrap();
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_name = CString::new(name)?;
         unsafe { ffi::gncCustomerSetName(self.ptr.as_ptr(), c_name.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the notes.
-    pub fn set_notes(&self, notes: &str) {
-        let c_notes = CString::new(notes).unwrap();
+    pub fn set_notes(&self, notes: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_notes = CString::new(notes)?;
         unsafe { ffi::gncCustomerSetNotes(self.ptr.as_ptr(), c_notes.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the discount.
-    pub fn set_discount(&self, discount: Numeric) {
+    pub fn set_discount(&self, discount: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncCustomerSetDiscount(self.ptr.as_ptr(), discount.into()) }
+        Ok(())
     }
 
     /// Sets the credit limit.
-    pub fn set_credit(&self, credit: Numeric) {
+    pub fn set_credit(&self, credit: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncCustomerSetCredit(self.ptr.as_ptr(), credit.into()) }
+        Ok(())
     }
 
     /// Sets the active flag.
-    pub fn set_active(&self, active: bool) {
+    pub fn set_active(&self, active: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncCustomerSetActive(self.ptr.as_ptr(), active as i32) }
+        Ok(())
     }
 
 }
 
-impl std::fmt::Debug for Customer {
+/// The engine cannot roll back customers, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Customer<'_> {
+    fn begin_edit(&self) {
+        Customer::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Customer::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for Customer<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Customer")
             .field("id", &self.id())
@@ -1,29 +1,32 @@
 //! Safe wrapper for GncEmployee.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Book, Guid, Numeric};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Book, Editable, Guid, Numeric, Result};
 
 use super::{Address, Owner};
 
 /// An employee entity.
-pub struct Employee {
+pub struct Employee<'book> {
     ptr: NonNull<ffi::GncEmployee>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Employee {}
+unsafe impl Send for Employee<'_> {}
 
-impl Employee {
+impl<'book> Employee<'book> {
     /// Creates a new Employee in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncEmployeeCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncEmployeeCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -32,7 +35,7 @@ impl Employee {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncEmployee, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -64,7 +67,7 @@ impl Employee {
     }
 
     /// Creates an Owner from this employee.
-    pub fn to_owner(&self) -> Owner {
+    pub fn to_owner(&self) -> Owner<'book> {
         let mut owner = Owner::new();
         unsafe { ffi::gncOwnerInitEmployee(owner.as_mut_ptr(), self.ptr.as_ptr()) };
         owner
@@ -109,7 +112,7 @@ impl Employee {
     }
 
     /// Returns the billing address.
-    pub fn addr(&self) -> Option<Address> {
+    pub fn addr(&self) -> Option<Address<'book>> {
         unsafe {
             let ptr = ffi::gncEmployeeGetAddr(self.ptr.as_ptr());
             Address::from_raw(ptr, false)
@@ -133,42 +136,71 @@ impl Employee {
 
     // ==================== Setters ====================
 
+    /// Checks that this employee can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "employee") }
+    }
+
     /// Sets the employee ID.
-    pub fn set_id(&self, id: &str) {
-        let c_id = CString::new(id).unwrap();
+    pub fn set_id(&self, id: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_id = CString::new(id)?;
         unsafe { ffi::gncEmployeeSetID(self.ptr.as_ptr(), c_id.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the employee username.
-    pub fn set_username(&self, username: &str) {
-        let c_username = CString::new(username).unwrap();
+    pub fn set_username(&self, username: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_username = CString::new(username)?;
         unsafe { ffi::gncEmployeeSetUsername(self.ptr.as_ptr(), c_username.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the language.
-    pub fn set_language(&self, language: &str) {
-        let c_language = CString::new(language).unwrap();
+    pub fn set_language(&self, language: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_language = CString::new(language)?;
         unsafe { ffi::gncEmployeeSetLanguage(self.ptr.as_ptr(), c_language.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the workday (hours per day).
-    pub fn set_workday(&self, workday: Numeric) {
+    pub fn set_workday(&self, workday: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEmployeeSetWorkday(self.ptr.as_ptr(), workday.into()) }
+        Ok(())
     }
 
     /// Sets the rate (hourly rate).
-    pub fn set_rate(&self, rate: Numeric) {
+    pub fn set_rate(&self, rate: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEmployeeSetRate(self.ptr.as_ptr(), rate.into()) }
+        Ok(())
     }
 
     /// Sets the active flag.
-    pub fn set_active(&self, active: bool) {
+    pub fn set_active(&self, active: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEmployeeSetActive(self.ptr.as_ptr(), active as i32) }
+        Ok(())
     }
 
 }
 
-impl std::fmt::Debug for Employee {
+/// The engine cannot roll back employees, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Employee<'_> {
+    fn begin_edit(&self) {
+        Employee::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Employee::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for Employee<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Employee")
             .field("id", &self.id())
@@ -1,29 +1,32 @@
 //! Safe wrapper for GncEntry.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Account, Book, Guid, Numeric};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Account, Book, Editable, Guid, Numeric, Result, Time64};
 
 use super::{Invoice, TaxTable};
 
 /// A line item in an invoice or bill.
-pub struct Entry {
+pub struct Entry<'book> {
     ptr: NonNull<ffi::GncEntry>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Entry {}
+unsafe impl Send for Entry<'_> {}
 
-impl Entry {
+impl<'book> Entry<'book> {
     /// Creates a new Entry in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncEntryCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncEntryCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -32,7 +35,7 @@ impl Entry {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncEntry, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -66,13 +69,13 @@ impl Entry {
     // ==================== Getters ====================
 
     /// Returns the entry date.
-    pub fn date(&self) -> i64 {
-        unsafe { ffi::gncEntryGetDate(self.ptr.as_ptr()) }
+    pub fn date(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::gncEntryGetDate(self.ptr.as_ptr()) })
     }
 
     /// Returns the date entered.
-    pub fn date_entered(&self) -> i64 {
-        unsafe { ffi::gncEntryGetDateEntered(self.ptr.as_ptr()) }
+    pub fn date_entered(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::gncEntryGetDateEntered(self.ptr.as_ptr()) })
     }
 
     /// Returns the description.
@@ -127,7 +130,7 @@ impl Entry {
     }
 
     /// Returns the invoice account.
-    pub fn inv_account(&self) -> Option<Account> {
+    pub fn inv_account(&self) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::gncEntryGetInvAccount(self.ptr.as_ptr());
             Account::from_raw(ptr, false)
@@ -140,7 +143,7 @@ impl Entry {
     }
 
     /// Returns the bill account.
-    pub fn bill_account(&self) -> Option<Account> {
+    pub fn bill_account(&self) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::gncEntryGetBillAccount(self.ptr.as_ptr());
             Account::from_raw(ptr, false)
@@ -148,7 +151,7 @@ impl Entry {
     }
 
     /// Returns the invoice this entry belongs to.
-    pub fn invoice(&self) -> Option<Invoice> {
+    pub fn invoice(&self) -> Option<Invoice<'book>> {
         unsafe {
             let ptr = ffi::gncEntryGetInvoice(self.ptr.as_ptr());
             Invoice::from_raw(ptr, false)
@@ -156,7 +159,7 @@ impl Entry {
     }
 
     /// Returns the bill this entry belongs to.
-    pub fn bill(&self) -> Option<Invoice> {
+    pub fn bill(&self) -> Option<Invoice<'book>> {
         unsafe {
             let ptr = ffi::gncEntryGetBill(self.ptr.as_ptr());
             Invoice::from_raw(ptr, false)
@@ -165,68 +168,95 @@ impl Entry {
 
     // ==================== Setters ====================
 
+    /// Checks that this entry can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "entry") }
+    }
+
     /// Sets the entry date.
-    pub fn set_date(&self, date: i64) {
-        unsafe { ffi::gncEntrySetDate(self.ptr.as_ptr(), date) }
+    pub fn set_date(&self, date: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gncEntrySetDate(self.ptr.as_ptr(), date.secs()) }
+        Ok(())
     }
 
     /// Sets the date entered.
-    pub fn set_date_entered(&self, date: i64) {
-        unsafe { ffi::gncEntrySetDateEntered(self.ptr.as_ptr(), date) }
+    pub fn set_date_entered(&self, date: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gncEntrySetDateEntered(self.ptr.as_ptr(), date.secs()) }
+        Ok(())
     }
 
     /// Sets the description.
-    pub fn set_description(&self, desc: &str) {
-        let c_desc = CString::new(desc).unwrap();
+    pub fn set_description(&self, desc: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_desc = CString::new(desc)?;
         unsafe { ffi::gncEntrySetDescription(self.ptr.as_ptr(), c_desc.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the action.
-    pub fn set_action(&self, action: &str) {
-        let c_action = CString::new(action).unwrap();
+    pub fn set_action(&self, action: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_action = CString::new(action)?;
         unsafe { ffi::gncEntrySetAction(self.ptr.as_ptr(), c_action.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the notes.
-    pub fn set_notes(&self, notes: &str) {
-        let c_notes = CString::new(notes).unwrap();
+    pub fn set_notes(&self, notes: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_notes = CString::new(notes)?;
         unsafe { ffi::gncEntrySetNotes(self.ptr.as_ptr(), c_notes.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the quantity.
-    pub fn set_quantity(&self, quantity: Numeric) {
+    pub fn set_quantity(&self, quantity: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetQuantity(self.ptr.as_ptr(), quantity.into()) }
+        Ok(())
     }
 
     /// Sets the invoice price.
-    pub fn set_inv_price(&self, price: Numeric) {
+    pub fn set_inv_price(&self, price: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetInvPrice(self.ptr.as_ptr(), price.into()) }
+        Ok(())
     }
 
     /// Sets the invoice discount.
-    pub fn set_inv_discount(&self, discount: Numeric) {
+    pub fn set_inv_discount(&self, discount: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetInvDiscount(self.ptr.as_ptr(), discount.into()) }
+        Ok(())
     }
 
     /// Sets the invoice account.
-    pub fn set_inv_account(&self, account: &Account) {
+    pub fn set_inv_account(&self, account: &Account) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetInvAccount(self.ptr.as_ptr(), account.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the bill price.
-    pub fn set_bill_price(&self, price: Numeric) {
+    pub fn set_bill_price(&self, price: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetBillPrice(self.ptr.as_ptr(), price.into()) }
+        Ok(())
     }
 
     /// Sets the bill account.
-    pub fn set_bill_account(&self, account: &Account) {
+    pub fn set_bill_account(&self, account: &Account) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetBillAccount(self.ptr.as_ptr(), account.as_ptr()) }
+        Ok(())
     }
 
     // ==================== Tax Table ====================
 
     /// Returns the invoice tax table.
-    pub fn inv_tax_table(&self) -> Option<TaxTable> {
+    pub fn inv_tax_table(&self) -> Option<TaxTable<'book>> {
         unsafe {
             let ptr = ffi::gncEntryGetInvTaxTable(self.ptr.as_ptr());
             TaxTable::from_raw(ptr, false)
@@ -234,8 +264,10 @@ impl Entry {
     }
 
     /// Sets the invoice tax table.
-    pub fn set_inv_tax_table(&self, table: &TaxTable) {
+    pub fn set_inv_tax_table(&self, table: &TaxTable) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetInvTaxTable(self.ptr.as_ptr(), table.as_ptr()) }
+        Ok(())
     }
 
     /// Returns true if tax is included in the invoice price.
@@ -244,8 +276,10 @@ impl Entry {
     }
 
     /// Sets whether tax is included in the invoice price.
-    pub fn set_inv_tax_included(&self, included: bool) {
+    pub fn set_inv_tax_included(&self, included: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetInvTaxIncluded(self.ptr.as_ptr(), included as i32) }
+        Ok(())
     }
 
     /// Returns true if the entry is taxable (invoice).
@@ -254,13 +288,27 @@ impl Entry {
     }
 
     /// Sets whether the entry is taxable (invoice).
-    pub fn set_inv_taxable(&self, taxable: bool) {
+    pub fn set_inv_taxable(&self, taxable: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncEntrySetInvTaxable(self.ptr.as_ptr(), taxable as i32) }
+        Ok(())
     }
 
 }
 
-impl std::fmt::Debug for Entry {
+/// The engine cannot roll back invoice entries, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Entry<'_> {
+    fn begin_edit(&self) {
+        Entry::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Entry::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for Entry<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Entry")
             .field("description", &self.description())
@@ -1,31 +1,34 @@
 //! Safe wrapper for GncInvoice.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Account, Book, Guid, Numeric, Transaction};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Account, Book, Editable, Guid, Numeric, Result, Time64, Transaction};
 
 use super::Owner;
 
 pub use ffi::GncInvoiceType as InvoiceType;
 
 /// An invoice or bill.
-pub struct Invoice {
+pub struct Invoice<'book> {
     ptr: NonNull<ffi::GncInvoice>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Invoice {}
+unsafe impl Send for Invoice<'_> {}
 
-impl Invoice {
+impl<'book> Invoice<'book> {
     /// Creates a new Invoice in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncInvoiceCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncInvoiceCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -34,7 +37,7 @@ impl Invoice {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncInvoice, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -104,7 +107,7 @@ impl Invoice {
     }
 
     /// Returns the owner of this invoice.
-    pub fn owner(&self) -> Owner {
+    pub fn owner(&self) -> Owner<'book> {
         unsafe {
             let owner_ptr = ffi::gncInvoiceGetOwner(self.ptr.as_ptr());
             if owner_ptr.is_null() {
@@ -116,18 +119,18 @@ impl Invoice {
     }
 
     /// Returns the date opened.
-    pub fn date_opened(&self) -> i64 {
-        unsafe { ffi::gncInvoiceGetDateOpened(self.ptr.as_ptr()) }
+    pub fn date_opened(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::gncInvoiceGetDateOpened(self.ptr.as_ptr()) })
     }
 
     /// Returns the date posted.
-    pub fn date_posted(&self) -> i64 {
-        unsafe { ffi::gncInvoiceGetDatePosted(self.ptr.as_ptr()) }
+    pub fn date_posted(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::gncInvoiceGetDatePosted(self.ptr.as_ptr()) })
     }
 
     /// Returns the date due.
-    pub fn date_due(&self) -> i64 {
-        unsafe { ffi::gncInvoiceGetDateDue(self.ptr.as_ptr()) }
+    pub fn date_due(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::gncInvoiceGetDateDue(self.ptr.as_ptr()) })
     }
 
     /// Returns the total amount.
@@ -161,7 +164,7 @@ impl Invoice {
     }
 
     /// Returns the posted transaction.
-    pub fn posted_txn(&self) -> Option<Transaction> {
+    pub fn posted_txn(&self) -> Option<Transaction<'book>> {
         unsafe {
             let ptr = ffi::gncInvoiceGetPostedTxn(self.ptr.as_ptr());
             Transaction::from_raw(ptr, false)
@@ -169,7 +172,7 @@ impl Invoice {
     }
 
     /// Returns the posted account.
-    pub fn posted_acc(&self) -> Option<Account> {
+    pub fn posted_acc(&self) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::gncInvoiceGetPostedAcc(self.ptr.as_ptr());
             Account::from_raw(ptr, false)
@@ -178,32 +181,47 @@ impl Invoice {
 
     // ==================== Setters ====================
 
+    /// Checks that this invoice can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "invoice") }
+    }
+
     /// Sets the invoice ID.
-    pub fn set_id(&self, id: &str) {
-        let c_id = CString::new(id).unwrap();
+    pub fn set_id(&self, id: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_id = CString::new(id)?;
         unsafe { ffi::gncInvoiceSetID(self.ptr.as_ptr(), c_id.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the invoice notes.
-    pub fn set_notes(&self, notes: &str) {
-        let c_notes = CString::new(notes).unwrap();
+    pub fn set_notes(&self, notes: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_notes = CString::new(notes)?;
         unsafe { ffi::gncInvoiceSetNotes(self.ptr.as_ptr(), c_notes.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the billing ID.
-    pub fn set_billing_id(&self, billing_id: &str) {
-        let c_billing_id = CString::new(billing_id).unwrap();
+    pub fn set_billing_id(&self, billing_id: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_billing_id = CString::new(billing_id)?;
         unsafe { ffi::gncInvoiceSetBillingID(self.ptr.as_ptr(), c_billing_id.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the owner of this invoice.
-    pub fn set_owner(&self, owner: &Owner) {
+    pub fn set_owner(&self, owner: &Owner) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncInvoiceSetOwner(self.ptr.as_ptr(), owner.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the date opened.
-    pub fn set_date_opened(&self, date: i64) {
-        unsafe { ffi::gncInvoiceSetDateOpened(self.ptr.as_ptr(), date) }
+    pub fn set_date_opened(&self, date: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gncInvoiceSetDateOpened(self.ptr.as_ptr(), date.secs()) }
+        Ok(())
     }
 
     // ==================== Entry Management ====================
@@ -220,7 +238,19 @@ impl Invoice {
 
 }
 
-impl std::fmt::Debug for Invoice {
+/// The engine cannot roll back invoices, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Invoice<'_> {
+    fn begin_edit(&self) {
+        Invoice::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Invoice::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for Invoice<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Invoice")
             .field("id", &self.id())
@@ -1,29 +1,32 @@
 //! Safe wrapper for GncJob.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Book, Guid};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Book, Editable, Guid, Result};
 
 use super::Owner;
 
 /// A job entity linked to a customer.
-pub struct Job {
+pub struct Job<'book> {
     ptr: NonNull<ffi::GncJob>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Job {}
+unsafe impl Send for Job<'_> {}
 
-impl Job {
+impl<'book> Job<'book> {
     /// Creates a new Job in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncJobCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncJobCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -32,7 +35,7 @@ impl Job {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncJob, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -64,7 +67,7 @@ impl Job {
     }
 
     /// Creates an Owner from this job.
-    pub fn to_owner(&self) -> Owner {
+    pub fn to_owner(&self) -> Owner<'book> {
         let mut owner = Owner::new();
         unsafe { ffi::gncOwnerInitJob(owner.as_mut_ptr(), self.ptr.as_ptr()) };
         owner
@@ -109,7 +112,7 @@ impl Job {
     }
 
     /// Returns the owner of this job.
-    pub fn owner(&self) -> Owner {
+    pub fn owner(&self) -> Owner<'book> {
         unsafe {
             let owner_ptr = ffi::gncJobGetOwner(self.ptr.as_ptr());
             if owner_ptr.is_null() {
@@ -127,37 +130,64 @@ impl Job {
 
     // ==================== Setters ====================
 
+    /// Checks that this job can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "job") }
+    }
+
     /// Sets the job ID.
-    pub fn set_id(&self, id: &str) {
-        let c_id = CString::new(id).unwrap();
+    pub fn set_id(&self, id: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_id = CString::new(id)?;
         unsafe { ffi::gncJobSetID(self.ptr.as_ptr(), c_id.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the job name.
-    pub fn set_name(&self, name: &str) {
-        let c_name = CString::new(name).unwrap();
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_name = CString::new(name)?;
         unsafe { ffi::gncJobSetName(self.ptr.as_ptr(), c_name.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the job reference.
-    pub fn set_reference(&self, reference: &str) {
-        let c_ref = CString::new(reference).unwrap();
+    pub fn set_reference(&self, reference: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_ref = CString::new(reference)?;
         unsafe { ffi::gncJobSetReference(self.ptr.as_ptr(), c_ref.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the owner of this job.
-    pub fn set_owner(&self, owner: &Owner) {
+    pub fn set_owner(&self, owner: &Owner) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncJobSetOwner(self.ptr.as_ptr(), owner.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the active flag.
-    pub fn set_active(&self, active: bool) {
+    pub fn set_active(&self, active: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncJobSetActive(self.ptr.as_ptr(), active as i32) }
+        Ok(())
     }
 
 }
 
-impl std::fmt::Debug for Job {
+/// The engine cannot roll back jobs, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Job<'_> {
+    fn begin_edit(&self) {
+        Job::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Job::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for Job<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Job")
             .field("id", &self.id())
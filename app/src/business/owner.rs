@@ -1,29 +1,37 @@
 //! Safe wrapper for GncOwner - polymorphic business owner.
 
 use std::ffi::CStr;
+use std::marker::PhantomData;
 
 use gnucash_sys::ffi;
-use gnucash_sys::Guid;
+use gnucash_sys::{Book, Guid};
 
 pub use ffi::GncOwnerType as OwnerType;
 
 /// A polymorphic owner - can be Customer, Vendor, Employee, or Job.
 #[repr(C)]
-pub struct Owner {
+pub struct Owner<'book> {
     inner: ffi::GncOwner,
+    _book: PhantomData<&'book Book>,
 }
 
-impl Owner {
+impl<'book> Owner<'book> {
     /// Creates a new undefined owner.
     pub fn new() -> Self {
         let mut inner = ffi::GncOwner::default();
         unsafe { ffi::gncOwnerInitUndefined(&mut inner, std::ptr::null_mut()) };
-        Self { inner }
+        Self {
+            inner,
+            _book: PhantomData,
+        }
     }
 
     /// Creates an Owner from a raw GncOwner.
     pub fn from_raw(raw: ffi::GncOwner) -> Self {
-        Self { inner: raw }
+        Self {
+            inner: raw,
+            _book: PhantomData,
+        }
     }
 
     /// Returns a mutable pointer to the inner GncOwner.
@@ -102,13 +110,13 @@ impl Owner {
     }
 }
 
-impl Default for Owner {
+impl Default for Owner<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Clone for Owner {
+impl Clone for Owner<'_> {
     fn clone(&self) -> Self {
         let mut new_owner = Self::new();
         unsafe { ffi::gncOwnerCopy(&self.inner, &mut new_owner.inner) };
@@ -116,7 +124,7 @@ impl Clone for Owner {
     }
 }
 
-impl std::fmt::Debug for Owner {
+impl std::fmt::Debug for Owner<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Owner")
             .field("type", &self.owner_type())
@@ -133,18 +141,18 @@ use super::{Customer, Employee, Job, Vendor};
 #[derive(Debug)]
 pub enum TypedOwner<'a> {
     /// A customer owner.
-    Customer(&'a Customer),
+    Customer(&'a Customer<'a>),
     /// A vendor owner.
-    Vendor(&'a Vendor),
+    Vendor(&'a Vendor<'a>),
     /// An employee owner.
-    Employee(&'a Employee),
+    Employee(&'a Employee<'a>),
     /// A job owner.
-    Job(&'a Job),
+    Job(&'a Job<'a>),
 }
 
 impl<'a> TypedOwner<'a> {
     /// Converts to an Owner for use with GnuCash APIs.
-    pub fn to_owner(&self) -> Owner {
+    pub fn to_owner(&self) -> Owner<'_> {
         match self {
             TypedOwner::Customer(c) => c.to_owner(),
             TypedOwner::Vendor(v) => v.to_owner(),
@@ -164,26 +172,26 @@ impl<'a> TypedOwner<'a> {
     }
 }
 
-impl<'a> From<&'a Customer> for TypedOwner<'a> {
-    fn from(customer: &'a Customer) -> Self {
+impl<'a> From<&'a Customer<'a>> for TypedOwner<'a> {
+    fn from(customer: &'a Customer<'a>) -> Self {
         TypedOwner::Customer(customer)
     }
 }
 
-impl<'a> From<&'a Vendor> for TypedOwner<'a> {
-    fn from(vendor: &'a Vendor) -> Self {
+impl<'a> From<&'a Vendor<'a>> for TypedOwner<'a> {
+    fn from(vendor: &'a Vendor<'a>) -> Self {
         TypedOwner::Vendor(vendor)
     }
 }
 
-impl<'a> From<&'a Employee> for TypedOwner<'a> {
-    fn from(employee: &'a Employee) -> Self {
+impl<'a> From<&'a Employee<'a>> for TypedOwner<'a> {
+    fn from(employee: &'a Employee<'a>) -> Self {
         TypedOwner::Employee(employee)
     }
 }
 
-impl<'a> From<&'a Job> for TypedOwner<'a> {
-    fn from(job: &'a Job) -> Self {
+impl<'a> From<&'a Job<'a>> for TypedOwner<'a> {
+    fn from(job: &'a Job<'a>) -> Self {
         TypedOwner::Job(job)
     }
 }
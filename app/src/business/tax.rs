@@ -1,29 +1,32 @@
 //! Safe wrapper for GncTaxTable and GncTaxTableEntry.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Account, Book, Guid, Numeric};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Account, Book, Editable, Guid, Numeric, Result};
 
 pub use ffi::GncAmountType as AmountType;
 
 /// A tax rate table.
-pub struct TaxTable {
+pub struct TaxTable<'book> {
     ptr: NonNull<ffi::GncTaxTable>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for TaxTable {}
+unsafe impl Send for TaxTable<'_> {}
 
-impl TaxTable {
+impl<'book> TaxTable<'book> {
     /// Creates a new TaxTable in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncTaxTableCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncTaxTableCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -32,7 +35,7 @@ impl TaxTable {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncTaxTable, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -78,7 +81,7 @@ impl TaxTable {
     }
 
     /// Returns the parent tax table.
-    pub fn parent(&self) -> Option<TaxTable> {
+    pub fn parent(&self) -> Option<TaxTable<'book>> {
         unsafe {
             let ptr = ffi::gncTaxTableGetParent(self.ptr.as_ptr());
             Self::from_raw(ptr, false)
@@ -91,14 +94,15 @@ impl TaxTable {
     }
 
     /// Returns the entries as a vector.
-    pub fn entries(&self) -> Vec<TaxTableEntry> {
+    pub fn entries(&self) -> Vec<TaxTableEntry<'book>> {
         let mut result = Vec::new();
         unsafe {
             let list = ffi::gncTaxTableGetEntries(self.ptr.as_ptr());
             let mut current = list;
             while !current.is_null() {
                 let entry_ptr = (*current).data as *mut ffi::GncTaxTableEntry;
-                if let Some(entry) = TaxTableEntry::from_raw(entry_ptr) {
+                if let Some(mut entry) = TaxTableEntry::from_raw(entry_ptr) {
+                    entry.table = Some(self.ptr);
                     result.push(entry);
                 }
                 current = (*current).next;
@@ -109,10 +113,17 @@ impl TaxTable {
 
     // ==================== Setters ====================
 
+    /// Checks that this tax table can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "tax table") }
+    }
+
     /// Sets the name.
-    pub fn set_name(&self, name: &str) {
-        let c_name = CString::new(name).unwrap();
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_name = CString::new(name)?;
         unsafe { ffi::gncTaxTableSetName(self.ptr.as_ptr(), c_name.as_ptr()) }
+        Ok(())
     }
 
     /// Adds an entry to this tax table.
@@ -128,7 +139,7 @@ impl TaxTable {
     // ==================== Lookup ====================
 
     /// Looks up a tax table by name.
-    pub fn lookup_by_name(book: &Book, name: &str) -> Option<Self> {
+    pub fn lookup_by_name(book: &'book Book, name: &str) -> Option<Self> {
         let c_name = CString::new(name).ok()?;
         unsafe {
             let ptr = ffi::gncTaxTableLookupByName(book.as_ptr(), c_name.as_ptr());
@@ -137,7 +148,19 @@ impl TaxTable {
     }
 }
 
-impl std::fmt::Debug for TaxTable {
+/// The engine cannot roll back tax tables, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for TaxTable<'_> {
+    fn begin_edit(&self) {
+        TaxTable::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        TaxTable::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for TaxTable<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TaxTable")
             .field("name", &self.name())
@@ -147,18 +170,23 @@ impl std::fmt::Debug for TaxTable {
 }
 
 /// An entry in a tax table.
-pub struct TaxTableEntry {
+pub struct TaxTableEntry<'book> {
     ptr: NonNull<ffi::GncTaxTableEntry>,
+    // The table the entry was read from, whose edit covers the setters
+    table: Option<NonNull<ffi::GncTaxTable>>,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for TaxTableEntry {}
+unsafe impl Send for TaxTableEntry<'_> {}
 
-impl TaxTableEntry {
+impl<'book> TaxTableEntry<'book> {
     /// Creates a new TaxTableEntry.
     pub fn new() -> Self {
         let ptr = unsafe { ffi::gncTaxTableEntryCreate() };
         Self {
             ptr: NonNull::new(ptr).expect("gncTaxTableEntryCreate returned null"),
+            table: None,
+            _book: PhantomData,
         }
     }
 
@@ -167,7 +195,11 @@ impl TaxTableEntry {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncTaxTableEntry) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr })
+        NonNull::new(ptr).map(|ptr| Self {
+            ptr,
+            table: None,
+            _book: PhantomData,
+        })
     }
 
     /// Returns the raw pointer.
@@ -178,7 +210,7 @@ impl TaxTableEntry {
     // ==================== Getters ====================
 
     /// Returns the account.
-    pub fn account(&self) -> Option<Account> {
+    pub fn account(&self) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::gncTaxTableEntryGetAccount(self.ptr.as_ptr());
             Account::from_raw(ptr, false)
@@ -197,29 +229,45 @@ impl TaxTableEntry {
 
     // ==================== Setters ====================
 
+    /// Checks that the table this entry was read from, if any, can be
+    /// modified. Entries from [`TaxTable::entries`] change their table, so
+    /// the table must be open for edit.
+    fn check_editable(&self) -> Result<()> {
+        match self.table {
+            Some(table) => unsafe { edit::check_editable(table.as_ptr().cast(), "tax table") },
+            None => Ok(()),
+        }
+    }
+
     /// Sets the account.
-    pub fn set_account(&self, account: &Account) {
+    pub fn set_account(&self, account: &Account) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncTaxTableEntrySetAccount(self.ptr.as_ptr(), account.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the amount type.
-    pub fn set_type(&self, amount_type: AmountType) {
+    pub fn set_type(&self, amount_type: AmountType) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncTaxTableEntrySetType(self.ptr.as_ptr(), amount_type) }
+        Ok(())
     }
 
     /// Sets the amount.
-    pub fn set_amount(&self, amount: Numeric) {
+    pub fn set_amount(&self, amount: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncTaxTableEntrySetAmount(self.ptr.as_ptr(), amount.into()) }
+        Ok(())
     }
 }
 
-impl Default for TaxTableEntry {
+impl Default for TaxTableEntry<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl std::fmt::Debug for TaxTableEntry {
+impl std::fmt::Debug for TaxTableEntry<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TaxTableEntry")
             .field("amount_type", &self.amount_type())
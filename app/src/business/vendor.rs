@@ -1,29 +1,32 @@
 //! Safe wrapper for GncVendor.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use gnucash_sys::ffi;
-use gnucash_sys::{Book, Guid};
+use gnucash_sys::{edit, ffi};
+use gnucash_sys::{Book, Editable, Guid, Result};
 
 use super::{Address, Owner};
 
 /// A vendor/supplier entity.
-pub struct Vendor {
+pub struct Vendor<'book> {
     ptr: NonNull<ffi::GncVendor>,
     #[allow(dead_code)]
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Vendor {}
+unsafe impl Send for Vendor<'_> {}
 
-impl Vendor {
+impl<'book> Vendor<'book> {
     /// Creates a new Vendor in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gncVendorCreate(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gncVendorCreate returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -32,7 +35,7 @@ impl Vendor {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::GncVendor, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer.
@@ -64,7 +67,7 @@ impl Vendor {
     }
 
     /// Creates an Owner from this vendor.
-    pub fn to_owner(&self) -> Owner {
+    pub fn to_owner(&self) -> Owner<'book> {
         let mut owner = Owner::new();
         unsafe { ffi::gncOwnerInitVendor(owner.as_mut_ptr(), self.ptr.as_ptr()) };
         owner
@@ -109,7 +112,7 @@ impl Vendor {
     }
 
     /// Returns the billing address.
-    pub fn addr(&self) -> Option<Address> {
+    pub fn addr(&self) -> Option<Address<'book>> {
         unsafe {
             let ptr = ffi::gncVendorGetAddr(self.ptr.as_ptr());
             Address::from_raw(ptr, false)
@@ -123,32 +126,57 @@ impl Vendor {
 
     // ==================== Setters ====================
 
+    /// Checks that this vendor can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "vendor") }
+    }
+
     /// Sets the vendor ID.
-    pub fn set_id(&self, id: &str) {
-        let c_id = CString::new(id).unwrap();
+    pub fn set_id(&self, id: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_id = CString::new(id)?;
         unsafe { ffi::gncVendorSetID(self.ptr.as_ptr(), c_id.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the vendor name.
-    pub fn set_name(&self, name: &str) {
-        let c_name = CString::new(name).unwrap();
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_name = CString::new(name)?;
         unsafe { ffi::gncVendorSetName(self.ptr.as_ptr(), c_name.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the notes.
-    pub fn set_notes(&self, notes: &str) {
-        let c_notes = CString::new(notes).unwrap();
+    pub fn set_notes(&self, notes: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_notes = CString::new(notes)?;
         unsafe { ffi::gncVendorSetNotes(self.ptr.as_ptr(), c_notes.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the active flag.
-    pub fn set_active(&self, active: bool) {
+    pub fn set_active(&self, active: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gncVendorSetActive(self.ptr.as_ptr(), active as i32) }
+        Ok(())
     }
 
 }
 
-impl std::fmt::Debug for Vendor {
+/// The engine cannot roll back vendors, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Vendor<'_> {
+    fn begin_edit(&self) {
+        Vendor::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Vendor::commit_edit(self)
+    }
+}
+
+impl std::fmt::Debug for Vendor<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Vendor")
             .field("id", &self.id())
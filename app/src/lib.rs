@@ -5,14 +5,15 @@
 //! This crate provides additional features on top of the core gnucash-sys library:
 //!
 //! - [`business`] - Business entities (Customer, Vendor, Employee, Invoice, etc.)
-//! - [`price`] - Price database and price entries
+//! - [`price`] - Price quote providers (Price and PriceDB come from gnucash-sys)
 //! - [`query`] - QOF query interface
 //! - [`builder`] - Builder patterns for entity creation
+//! - [`template`] - Chart-of-accounts templates (.gnucash-xea and JSON)
 
 // Re-export gnucash-sys for convenience
 pub use gnucash_sys;
 
-/// Safe wrapper for GncPrice and GncPriceDB.
+/// Price database (shared with gnucash-sys) and quote providers.
 pub mod price;
 
 /// Safe wrapper for QofQuery.
@@ -24,20 +25,33 @@ pub mod builder;
 /// Business entities (Customer, Vendor, Employee, Invoice, etc.).
 pub mod business;
 
+/// Chart-of-accounts templates and their importer.
+pub mod template;
+
 // Re-export commonly used types from gnucash-sys
 pub use gnucash_sys::{
-    init_engine, is_engine_initialized, Account, Book, Error, GNCAccountType, Guid, Numeric,
-    Result, Session, SessionOpenMode, Split, Transaction,
+    init_engine, is_engine_initialized, Account, Book, BookRef, Commodity, CommodityTable, EditGuard,
+    Editable, Error, GNCAccountType, GncDate, Guid, Numeric, Result, Session, SessionOpenMode,
+    SessionOptions, Split, Time64, Transaction,
 };
 
 // Re-export price types
-pub use price::{Price, PriceDB, PriceSource};
+pub use price::{
+    update_prices, FileQuoteProvider, Price, PriceDB, PriceSource, Quote, QuoteFileFormat,
+    QuoteProvider, QuoteSummary,
+};
 
 // Re-export query types
 pub use query::{obj_types, params, QofQueryOp, Query};
 
 // Re-export builders
-pub use builder::{InvoiceBuilder, TransactionBuilder};
+pub use builder::{AccountTreeBuilder, InvoiceBuilder, TransactionBuilder};
+
+// Re-export template types
+pub use template::{
+    AccountTemplate, ChartTemplate, ImportReport, TemplateCommodity, TemplateFormat,
+    TemplateImporter,
+};
 
 // Re-export business entities
 pub use business::{
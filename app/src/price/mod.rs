@@ -0,0 +1,13 @@
+//! Price and PriceDB for gnucash-ext.
+//!
+//! The wrappers themselves live in [`gnucash_sys::price`] and are re-exported
+//! here, so prices can be passed between the two crates. The [`quote`]
+//! submodule adds pluggable online/offline quote providers.
+
+pub mod quote;
+
+pub use gnucash_sys::price::{Price, PriceDB, PriceSource};
+
+pub use quote::{
+    update_prices, FileQuoteProvider, Quote, QuoteFileFormat, QuoteProvider, QuoteSummary,
+};
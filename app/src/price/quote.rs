@@ -0,0 +1,475 @@
+//! Price quote providers.
+//!
+//! A [`QuoteProvider`] fetches prices for the commodities a book marks as
+//! quotable (see [`Commodity::quote_flag`]). [`update_prices`] drives a
+//! provider and stores its quotes in the book's [`PriceDB`] as
+//! Finance::Quote prices, skipping any commodity/currency pair that already
+//! has a price on the same day.
+//!
+//! [`FileQuoteProvider`] reads quotes from a local CSV or JSON file, which is
+//! useful for air-gapped machines and tests. Online sources live outside this
+//! crate: implement [`QuoteProvider`] for an HTTP client and pass it to
+//! [`update_prices`].
+
+use std::path::{Path, PathBuf};
+
+use gnucash_sys::{Book, Commodity, Editable, Error, GncDate, Numeric, Result, Time64};
+
+use super::{Price, PriceDB, PriceSource};
+
+/// A single price quote returned by a [`QuoteProvider`].
+///
+/// Commodities are identified by namespace and mnemonic, and the currency by
+/// its ISO code, so providers do not need to hold engine objects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    /// Commodity namespace (e.g. "NASDAQ", "FUND").
+    pub namespace: String,
+    /// Commodity mnemonic (e.g. "AAPL").
+    pub mnemonic: String,
+    /// ISO 4217 code of the currency the price is expressed in.
+    pub currency: String,
+    /// Time of the quote.
+    pub time: Time64,
+    /// Price of one unit of the commodity in `currency`.
+    pub value: Numeric,
+    /// Price type ("last", "bid", "ask", "nav", ...); "last" if unset.
+    pub type_string: Option<String>,
+}
+
+impl Quote {
+    /// Creates a "last" quote for `commodity`.
+    pub fn new(commodity: &Commodity, currency: &str, time: Time64, value: Numeric) -> Self {
+        Self {
+            namespace: commodity.namespace().unwrap_or_default(),
+            mnemonic: commodity.mnemonic().unwrap_or_default(),
+            currency: currency.to_string(),
+            time,
+            value,
+            type_string: None,
+        }
+    }
+
+    /// Returns true if this quote is for `commodity`.
+    pub fn is_for(&self, commodity: &Commodity) -> bool {
+        commodity.namespace().as_deref() == Some(self.namespace.as_str())
+            && commodity.mnemonic().as_deref() == Some(self.mnemonic.as_str())
+    }
+}
+
+/// A source of price quotes.
+///
+/// Implement this for online services in downstream crates; the trait has no
+/// dependency on any particular transport.
+pub trait QuoteProvider {
+    /// Fetches quotes for `commodities`.
+    ///
+    /// Quotes for commodities outside the slice are ignored by
+    /// [`update_prices`]. Commodities the provider knows nothing about
+    /// should simply be left out rather than reported as errors.
+    fn fetch(&mut self, commodities: &[Commodity]) -> Result<Vec<Quote>>;
+}
+
+impl<P: QuoteProvider + ?Sized> QuoteProvider for &mut P {
+    fn fetch(&mut self, commodities: &[Commodity]) -> Result<Vec<Quote>> {
+        (**self).fetch(commodities)
+    }
+}
+
+impl<P: QuoteProvider + ?Sized> QuoteProvider for Box<P> {
+    fn fetch(&mut self, commodities: &[Commodity]) -> Result<Vec<Quote>> {
+        (**self).fetch(commodities)
+    }
+}
+
+/// Outcome of an [`update_prices`] run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuoteSummary {
+    /// Prices added to the database.
+    pub added: usize,
+    /// Quotes skipped because a price already existed on that day.
+    pub duplicates: usize,
+    /// Quotes skipped because the commodity is not quotable in the book or
+    /// the currency is unknown.
+    pub unknown: usize,
+}
+
+/// Fetches quotes for the book's quotable commodities and adds them to its
+/// price database.
+///
+/// New prices get source [`PriceSource::PRICE_SOURCE_FQ`]. A quote is
+/// skipped if the database already holds a price for the same commodity and
+/// currency on the same day, including one added earlier in this run.
+pub fn update_prices<P: QuoteProvider + ?Sized>(
+    book: &Book,
+    provider: &mut P,
+) -> Result<QuoteSummary> {
+    let table = book
+        .commodity_table()
+        .ok_or(Error::NullPointer("commodity table"))?;
+    let pricedb = PriceDB::get(book).ok_or(Error::NullPointer("price database"))?;
+
+    let mut summary = QuoteSummary::default();
+    // Not `quotable_commodities`, which also requires a supported quote source
+    let commodities: Vec<_> = table
+        .commodities()
+        .into_iter()
+        .filter(|c| c.quote_flag())
+        .collect();
+    if commodities.is_empty() {
+        return Ok(summary);
+    }
+
+    let quotes = provider.fetch(&commodities)?;
+
+    let edit = pricedb.edit();
+    for quote in &quotes {
+        let commodity = commodities.iter().find(|c| quote.is_for(c));
+        let currency = table.lookup_currency(&quote.currency);
+        let (Some(commodity), Some(currency)) = (commodity, currency) else {
+            summary.unknown += 1;
+            continue;
+        };
+
+        if pricedb.lookup_day(commodity, &currency, quote.time).is_some() {
+            summary.duplicates += 1;
+            continue;
+        }
+
+        let price = Price::new(book);
+        price.edit_with(|price| {
+            price.set_commodity(commodity)?;
+            price.set_currency(&currency)?;
+            price.set_time(quote.time)?;
+            price.set_value(quote.value)?;
+            price.set_source(PriceSource::PRICE_SOURCE_FQ)?;
+            price.set_type_string(quote.type_string.as_deref().unwrap_or("last"))
+        })?;
+
+        if pricedb.add_price(&price) {
+            summary.added += 1;
+        }
+    }
+    edit.commit();
+
+    Ok(summary)
+}
+
+// ==================== File provider ====================
+
+/// Format of a quote file read by [`FileQuoteProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteFileFormat {
+    /// Comma-separated lines of `namespace,mnemonic,currency,date,value[,type]`.
+    ///
+    /// Blank lines, lines starting with `#` and a leading header line whose
+    /// first field is `namespace` are ignored.
+    Csv,
+    /// An array of objects with `namespace`, `mnemonic`, `currency`, `date`,
+    /// `value` and optional `type` keys. `value` may be a string or a number.
+    ///
+    /// Requires the `json` feature.
+    Json,
+}
+
+impl QuoteFileFormat {
+    /// Guesses the format from a file extension, defaulting to CSV.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => QuoteFileFormat::Json,
+            _ => QuoteFileFormat::Csv,
+        }
+    }
+
+    /// Parses quote file contents in this format.
+    ///
+    /// Dates are either `YYYY-MM-DD`, stored at the timezone-neutral time of
+    /// that day, or full ISO 8601 timestamps. Values are decimal strings.
+    pub fn parse(self, text: &str) -> Result<Vec<Quote>> {
+        match self {
+            QuoteFileFormat::Csv => parse_csv(text),
+            QuoteFileFormat::Json => parse_json(text),
+        }
+    }
+}
+
+/// A [`QuoteProvider`] that reads quotes from a local file.
+///
+/// The file is re-read on every [`fetch`](QuoteProvider::fetch), so it can be
+/// updated between runs.
+#[derive(Debug, Clone)]
+pub struct FileQuoteProvider {
+    path: PathBuf,
+    format: QuoteFileFormat,
+}
+
+impl FileQuoteProvider {
+    /// Creates a provider for `path`, choosing the format from its extension.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let format = QuoteFileFormat::from_path(&path);
+        Self { path, format }
+    }
+
+    /// Creates a provider for `path` with an explicit format.
+    pub fn with_format(path: impl Into<PathBuf>, format: QuoteFileFormat) -> Self {
+        Self {
+            path: path.into(),
+            format,
+        }
+    }
+
+    /// Returns the file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the file format.
+    pub fn format(&self) -> QuoteFileFormat {
+        self.format
+    }
+}
+
+impl QuoteProvider for FileQuoteProvider {
+    fn fetch(&mut self, commodities: &[Commodity]) -> Result<Vec<Quote>> {
+        let text = std::fs::read_to_string(&self.path)
+            .map_err(|e| Error::Other(format!("{}: {}", self.path.display(), e)))?;
+        let quotes = self.format.parse(&text)?;
+        Ok(quotes
+            .into_iter()
+            .filter(|q| commodities.iter().any(|c| q.is_for(c)))
+            .collect())
+    }
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Quote>> {
+    let mut quotes = Vec::new();
+    let mut first = true;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if first && fields[0].eq_ignore_ascii_case("namespace") {
+            first = false;
+            continue;
+        }
+        first = false;
+
+        let at_line = |e: Error| Error::InvalidOperation(format!("line {}: {}", index + 1, e));
+        if fields.len() < 5 || fields.len() > 6 {
+            return Err(at_line(Error::InvalidOperation(format!(
+                "expected 5 or 6 fields, found {}",
+                fields.len()
+            ))));
+        }
+        let type_string = fields.get(5).filter(|t| !t.is_empty()).copied();
+        quotes.push(
+            make_quote(fields[0], fields[1], fields[2], fields[3], fields[4], type_string)
+                .map_err(at_line)?,
+        );
+    }
+    Ok(quotes)
+}
+
+#[cfg(feature = "json")]
+fn parse_json(text: &str) -> Result<Vec<Quote>> {
+    #[derive(serde::Deserialize)]
+    struct Record {
+        namespace: String,
+        mnemonic: String,
+        currency: String,
+        date: String,
+        value: serde_json::Value,
+        #[serde(rename = "type")]
+        type_string: Option<String>,
+    }
+
+    let records: Vec<Record> = serde_json::from_str(text)
+        .map_err(|e| Error::InvalidOperation(format!("invalid quote file: {}", e)))?;
+    records
+        .iter()
+        .enumerate()
+        .map(|(index, r)| {
+            let value = match &r.value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            make_quote(
+                &r.namespace,
+                &r.mnemonic,
+                &r.currency,
+                &r.date,
+                &value,
+                r.type_string.as_deref(),
+            )
+            .map_err(|e| Error::InvalidOperation(format!("quote {}: {}", index, e)))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "json"))]
+fn parse_json(_text: &str) -> Result<Vec<Quote>> {
+    Err(Error::InvalidOperation(
+        "JSON quote files require the `json` feature".to_string(),
+    ))
+}
+
+fn make_quote(
+    namespace: &str,
+    mnemonic: &str,
+    currency: &str,
+    date: &str,
+    value: &str,
+    type_string: Option<&str>,
+) -> Result<Quote> {
+    let time = match date.parse::<GncDate>() {
+        Ok(day) => day.day_neutral(),
+        Err(_) => date.parse::<Time64>()?,
+    };
+    Ok(Quote {
+        namespace: namespace.to_string(),
+        mnemonic: mnemonic.to_string(),
+        currency: currency.to_string(),
+        time,
+        value: Numeric::parse_decimal(value)?,
+        type_string: type_string.map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use gnucash_sys::commodity::namespace;
+
+    struct FixedProvider(Vec<Quote>);
+
+    impl QuoteProvider for FixedProvider {
+        fn fetch(&mut self, _commodities: &[Commodity]) -> Result<Vec<Quote>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn quotable<'a>(book: &'a Book, mnemonic: &str) -> Commodity<'a> {
+        let table = book.commodity_table().unwrap();
+        let commodity =
+            Commodity::new(book, mnemonic, namespace::NASDAQ, mnemonic, None, 10000).unwrap();
+        commodity.edit_with(|c| c.set_quote_flag(true)).unwrap();
+        table.insert(commodity)
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "\
+# prices for 2024-01-02
+namespace,mnemonic,currency,date,value,type
+NASDAQ,AAPL,USD,2024-01-02,185.64
+FUND, VTSAX ,USD,2024-01-02T16:00:00Z,112.5,nav
+";
+        let quotes = QuoteFileFormat::Csv.parse(text).unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].mnemonic, "AAPL");
+        assert_eq!(quotes[0].value, Numeric::new(18564, 100));
+        assert_eq!(quotes[0].type_string, None);
+        assert_eq!(
+            quotes[0].time,
+            GncDate::from_dmy(2, 1, 2024).unwrap().day_neutral()
+        );
+        assert_eq!(quotes[1].mnemonic, "VTSAX");
+        assert_eq!(quotes[1].type_string.as_deref(), Some("nav"));
+    }
+
+    #[test]
+    fn test_parse_csv_errors() {
+        let err = QuoteFileFormat::Csv.parse("NASDAQ,AAPL,USD\n").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+        assert!(QuoteFileFormat::Csv.parse("NASDAQ,AAPL,USD,someday,1\n").is_err());
+        assert!(QuoteFileFormat::Csv.parse("NASDAQ,AAPL,USD,2024-01-02,abc\n").is_err());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            QuoteFileFormat::from_path(Path::new("quotes.JSON")),
+            QuoteFileFormat::Json
+        );
+        assert_eq!(
+            QuoteFileFormat::from_path(Path::new("quotes.csv")),
+            QuoteFileFormat::Csv
+        );
+        assert_eq!(
+            QuoteFileFormat::from_path(Path::new("quotes")),
+            QuoteFileFormat::Csv
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parse_json() {
+        let text = r#"[
+            {"namespace": "NASDAQ", "mnemonic": "AAPL", "currency": "USD",
+             "date": "2024-01-02", "value": "185.64"},
+            {"namespace": "FUND", "mnemonic": "VTSAX", "currency": "USD",
+             "date": "2024-01-02", "value": 112.5, "type": "nav"}
+        ]"#;
+        let quotes = QuoteFileFormat::Json.parse(text).unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].value, Numeric::new(18564, 100));
+        assert_eq!(quotes[1].value, Numeric::new(1125, 10));
+        assert_eq!(quotes[1].type_string.as_deref(), Some("nav"));
+    }
+
+    #[test]
+    fn test_update_prices_dedupes_same_day() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let aapl = quotable(&book, "AAPL");
+        let day = GncDate::from_dmy(2, 1, 2024).unwrap();
+
+        let mut provider = FixedProvider(vec![
+            Quote::new(&aapl, "USD", day.day_neutral(), Numeric::new(18564, 100)),
+            Quote::new(&aapl, "USD", day.day_end(), Numeric::new(18600, 100)),
+            Quote::new(&aapl, "XXX-NOT-A-CURRENCY", day.day_neutral(), Numeric::new(1, 1)),
+        ]);
+
+        let summary = update_prices(&book, &mut provider).unwrap();
+        assert_eq!(
+            summary,
+            QuoteSummary {
+                added: 1,
+                duplicates: 1,
+                unknown: 1
+            }
+        );
+
+        let pricedb = PriceDB::get(&book).unwrap();
+        let usd = table.lookup_currency("USD").unwrap();
+        let price = pricedb.latest(&aapl, &usd).unwrap();
+        assert_eq!(price.value(), Numeric::new(18564, 100));
+        assert_eq!(price.source(), PriceSource::PRICE_SOURCE_FQ);
+        assert_eq!(price.type_string().as_deref(), Some("last"));
+
+        let again = update_prices(&book, &mut provider).unwrap();
+        assert_eq!(again.added, 0);
+        assert_eq!(pricedb.num_prices(), 1);
+    }
+
+    #[test]
+    fn test_update_prices_ignores_unquoted() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let aapl = quotable(&book, "AAPL");
+        aapl.edit_with(|c| c.set_quote_flag(false)).unwrap();
+        let _msft = quotable(&book, "MSFT");
+
+        let now = Time64::now();
+        let mut provider = FixedProvider(vec![Quote::new(&aapl, "USD", now, Numeric::new(1, 1))]);
+        let summary = update_prices(&book, &mut provider).unwrap();
+        assert_eq!(summary.added, 0);
+        assert_eq!(summary.unknown, 1);
+    }
+}
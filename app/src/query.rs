@@ -1,35 +1,40 @@
 //! Safe wrapper for QofQuery - the GnuCash query framework.
 
 use std::ffi::CString;
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
 use gnucash_sys::ffi;
-use gnucash_sys::{Account, Book, Guid, Split, Transaction};
+use gnucash_sys::{Account, Book, Guid, Result, Split, Transaction};
 
 /// Re-export query enums.
 pub use gnucash_sys::ffi::QofQueryOp;
 
 /// A query for searching GnuCash objects.
-pub struct Query {
+pub struct Query<'book> {
     ptr: NonNull<ffi::QofQuery>,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Query {}
+unsafe impl Send for Query<'_> {}
 
-impl Query {
+impl<'book> Query<'book> {
     /// Creates a new query.
     pub fn new() -> Self {
         let ptr = unsafe { ffi::qof_query_create() };
         Self {
             ptr: NonNull::new(ptr).expect("qof_query_create returned null"),
+            _book: PhantomData,
         }
     }
 
     /// Creates a new query for a specific object type.
-    pub fn for_type(obj_type: &str) -> Self {
+    ///
+    /// Returns `Error::StringConversion` if `obj_type` contains a null byte.
+    pub fn for_type(obj_type: &str) -> Result<Self> {
         let query = Self::new();
-        query.set_search_for(obj_type);
-        query
+        query.set_search_for(obj_type)?;
+        Ok(query)
     }
 
     /// Creates a Query wrapper from a raw pointer.
@@ -37,7 +42,10 @@ impl Query {
     /// # Safety
     /// The pointer must be valid.
     pub unsafe fn from_raw(ptr: *mut ffi::QofQuery) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr })
+        NonNull::new(ptr).map(|ptr| Self {
+            ptr,
+            _book: PhantomData,
+        })
     }
 
     /// Returns the raw pointer.
@@ -46,13 +54,14 @@ impl Query {
     }
 
     /// Sets the object type to search for.
-    pub fn set_search_for(&self, obj_type: &str) {
-        let c_type = CString::new(obj_type).unwrap();
+    pub fn set_search_for(&self, obj_type: &str) -> Result<()> {
+        let c_type = CString::new(obj_type)?;
         unsafe { ffi::qof_query_search_for(self.ptr.as_ptr(), c_type.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the book to search in.
-    pub fn set_book(&self, book: &Book) {
+    pub fn set_book(&self, book: &'book Book) {
         unsafe { ffi::qof_query_set_book(self.ptr.as_ptr(), book.as_ptr()) }
     }
 
@@ -77,7 +86,7 @@ impl Query {
     }
 
     /// Runs the query and returns results as splits.
-    pub fn run_splits(&self) -> Vec<Split> {
+    pub fn run_splits(&self) -> Vec<Split<'book>> {
         let list = self.run_raw();
         let mut results = Vec::new();
         let mut current = list;
@@ -94,7 +103,7 @@ impl Query {
     }
 
     /// Runs the query and returns results as transactions.
-    pub fn run_transactions(&self) -> Vec<Transaction> {
+    pub fn run_transactions(&self) -> Vec<Transaction<'book>> {
         let list = self.run_raw();
         let mut results = Vec::new();
         let mut current = list;
@@ -111,7 +120,7 @@ impl Query {
     }
 
     /// Runs the query and returns results as accounts.
-    pub fn run_accounts(&self) -> Vec<Account> {
+    pub fn run_accounts(&self) -> Vec<Account<'book>> {
         let list = self.run_raw();
         let mut results = Vec::new();
         let mut current = list;
@@ -130,8 +139,8 @@ impl Query {
     // ==================== Predicate methods ====================
 
     /// Adds a GUID match predicate.
-    pub fn add_guid_match(&self, param_list: &[&str], guid: &Guid, op: QofQueryOp) {
-        let c_params = make_gsl(param_list);
+    pub fn add_guid_match(&self, param_list: &[&str], guid: &Guid, op: QofQueryOp) -> Result<()> {
+        let c_params = make_gsl(param_list)?;
         unsafe {
             ffi::qof_query_add_guid_match(
                 self.ptr.as_ptr(),
@@ -141,15 +150,22 @@ impl Query {
             );
         }
         // Note: qof_query takes ownership of the param_list
+        Ok(())
     }
 
     /// Adds a boolean match predicate.
-    pub fn add_boolean_match(&self, param_list: &[&str], value: bool, op: QofQueryOp) {
-        let c_params = make_gsl(param_list);
+    pub fn add_boolean_match(
+        &self,
+        param_list: &[&str],
+        value: bool,
+        op: QofQueryOp,
+    ) -> Result<()> {
+        let c_params = make_gsl(param_list)?;
         unsafe {
             ffi::qof_query_add_boolean_match(self.ptr.as_ptr(), c_params, value as i32, op);
         }
         // Note: qof_query takes ownership of the param_list
+        Ok(())
     }
 
     /// Merges another query into this one.
@@ -158,7 +174,7 @@ impl Query {
     }
 
     /// Inverts the query.
-    pub fn invert(&self) -> Query {
+    pub fn invert(&self) -> Query<'book> {
         unsafe {
             let ptr = ffi::qof_query_invert(self.ptr.as_ptr());
             Query::from_raw(ptr).expect("qof_query_invert returned null")
@@ -176,13 +192,13 @@ impl Query {
     }
 }
 
-impl Default for Query {
+impl Default for Query<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Clone for Query {
+impl Clone for Query<'_> {
     fn clone(&self) -> Self {
         unsafe {
             let ptr = ffi::qof_query_copy(self.ptr.as_ptr());
@@ -191,13 +207,13 @@ impl Clone for Query {
     }
 }
 
-impl Drop for Query {
+impl Drop for Query<'_> {
     fn drop(&mut self) {
         unsafe { ffi::qof_query_destroy(self.ptr.as_ptr()) }
     }
 }
 
-impl std::fmt::Debug for Query {
+impl std::fmt::Debug for Query<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Query")
             .field("has_terms", &self.has_terms())
@@ -208,15 +224,19 @@ impl std::fmt::Debug for Query {
 
 // Helper to create a GSList from a slice of strings
 // Note: qof_query takes ownership of this list, so we don't free it
-fn make_gsl(strings: &[&str]) -> *mut ffi::GSList {
+// All strings are converted first, so a null byte leaks nothing
+fn make_gsl(strings: &[&str]) -> Result<*mut ffi::GSList> {
+    let c_strs = strings
+        .iter()
+        .map(|s| CString::new(*s))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
     let mut list: *mut ffi::GSList = std::ptr::null_mut();
-    for s in strings.iter().rev() {
-        let c_str = CString::new(*s).unwrap();
+    for c_str in c_strs.into_iter().rev() {
         unsafe {
             list = ffi::g_slist_prepend(list, c_str.into_raw() as *mut _);
         }
     }
-    list
+    Ok(list)
 }
 
 /// Common object type constants for queries.
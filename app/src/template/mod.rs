@@ -0,0 +1,559 @@
+//! Chart-of-accounts templates.
+//!
+//! A [`ChartTemplate`] is a tree of [`AccountTemplate`]s read from one of
+//! GnuCash's `.gnucash-xea` account templates, or from a simpler JSON tree
+//! (see [`TemplateFormat::Json`]). [`TemplateImporter`] creates its accounts
+//! under the book's root account, or reports what it would create.
+//!
+//! The template types implement `serde::Deserialize` with the `json`
+//! feature, so trees in other formats such as YAML can be read with the
+//! matching serde crate (e.g. `serde_yaml::from_str::<ChartTemplate>`).
+
+mod xea;
+
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::path::Path;
+
+use gnucash_sys::commodity::namespace;
+use gnucash_sys::{
+    ffi, Account, Book, Commodity, CommodityTable, Editable, Error, GNCAccountType, Result,
+};
+
+/// A chart of accounts: a tree of accounts to create under a book's root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(deny_unknown_fields))]
+pub struct ChartTemplate {
+    /// The template title.
+    pub title: Option<String>,
+    /// A short description of the template.
+    pub description: Option<String>,
+    /// The top-level accounts.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub accounts: Vec<AccountTemplate>,
+}
+
+/// An account in a [`ChartTemplate`], with its children.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(deny_unknown_fields))]
+pub struct AccountTemplate {
+    /// The account name, without its parents.
+    pub name: String,
+    /// The account type as GnuCash writes it, e.g. `"BANK"` or `"EXPENSE"`.
+    #[cfg_attr(feature = "json", serde(rename = "type"))]
+    pub account_type: String,
+    /// The account code.
+    pub code: Option<String>,
+    /// The account description.
+    pub description: Option<String>,
+    /// The account commodity. Accounts without one take their parent's.
+    pub commodity: Option<TemplateCommodity>,
+    /// Whether the account only holds other accounts.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub placeholder: bool,
+    /// Whether the account is hidden.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub hidden: bool,
+    /// Whether the account is tax-related.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub tax_related: bool,
+    /// The US tax code (TXF code, e.g. `"N261"`).
+    pub tax_code: Option<String>,
+    /// Where the US tax payer name comes from (`"current"` or `"parent"`).
+    pub tax_source: Option<String>,
+    /// The child accounts.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub children: Vec<AccountTemplate>,
+}
+
+/// A commodity named by a template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(deny_unknown_fields))]
+pub struct TemplateCommodity {
+    /// The commodity namespace, e.g. `"CURRENCY"`, `"ISO4217"` or `"NASDAQ"`.
+    /// Defaults to `"CURRENCY"` in JSON templates.
+    #[cfg_attr(feature = "json", serde(default = "currency_namespace"))]
+    pub namespace: String,
+    /// The commodity mnemonic, e.g. `"USD"`.
+    pub mnemonic: String,
+}
+
+#[cfg(feature = "json")]
+fn currency_namespace() -> String {
+    namespace::CURRENCY.to_string()
+}
+
+impl TemplateCommodity {
+    /// Returns true for currencies and for the `template` placeholder
+    /// commodity, both of which [`TemplateImporter::currency`] replaces.
+    pub fn is_currency(&self) -> bool {
+        matches!(
+            self.namespace.as_str(),
+            namespace::CURRENCY | "ISO4217" | namespace::TEMPLATE
+        )
+    }
+
+    /// Returns the namespace, with the legacy `"ISO4217"` mapped to
+    /// `"CURRENCY"`.
+    fn engine_namespace(&self) -> &str {
+        match self.namespace.as_str() {
+            "ISO4217" => namespace::CURRENCY,
+            other => other,
+        }
+    }
+}
+
+impl ChartTemplate {
+    /// Parses a template in `format`.
+    pub fn parse(text: &str, format: TemplateFormat) -> Result<Self> {
+        format.parse(text)
+    }
+
+    /// Reads a template file, choosing the format from its extension.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::Other(format!("{}: {}", path.display(), e)))?;
+        TemplateFormat::from_path(path).parse(&text)
+    }
+
+    /// Returns the number of accounts in the template, at every level.
+    pub fn account_count(&self) -> usize {
+        fn count(accounts: &[AccountTemplate]) -> usize {
+            accounts.iter().map(|a| 1 + count(&a.children)).sum()
+        }
+        count(&self.accounts)
+    }
+}
+
+/// Format of a chart-of-accounts template file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateFormat {
+    /// GnuCash's `.gnucash-xea` account template XML.
+    Xea,
+    /// An object with optional `title` and `description` keys and an
+    /// `accounts` array. Each account has `name` and `type` keys and
+    /// optional `code`, `description`, `commodity` (`namespace`,
+    /// `mnemonic`), `placeholder`, `hidden`, `tax_related`, `tax_code`,
+    /// `tax_source` and `children` keys.
+    ///
+    /// Requires the `json` feature.
+    Json,
+}
+
+impl TemplateFormat {
+    /// Guesses the format from a file extension, defaulting to xea.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => TemplateFormat::Json,
+            _ => TemplateFormat::Xea,
+        }
+    }
+
+    /// Parses template file contents in this format.
+    pub fn parse(self, text: &str) -> Result<ChartTemplate> {
+        match self {
+            TemplateFormat::Xea => xea::parse(text),
+            TemplateFormat::Json => parse_json(text),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn parse_json(text: &str) -> Result<ChartTemplate> {
+    serde_json::from_str(text)
+        .map_err(|e| Error::InvalidOperation(format!("invalid template: {}", e)))
+}
+
+#[cfg(not(feature = "json"))]
+fn parse_json(_text: &str) -> Result<ChartTemplate> {
+    Err(Error::InvalidOperation(
+        "JSON templates require the `json` feature".to_string(),
+    ))
+}
+
+// ==================== Importer ====================
+
+/// What [`TemplateImporter::import`] created, or would create in a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Full names of the accounts created, parents first.
+    pub created: Vec<String>,
+    /// Full names of template accounts that already existed. These are left
+    /// unchanged.
+    pub existing: Vec<String>,
+}
+
+/// Creates the accounts of a [`ChartTemplate`] under a book's root account.
+///
+/// Accounts already in the book, matched by full name, are kept as they are;
+/// only missing ones are created, with the template's code, description,
+/// commodity, placeholder, hidden and tax settings. The whole template is
+/// checked before anything is created, so an unknown account type or
+/// commodity leaves the book untouched.
+///
+/// # Example
+/// ```ignore
+/// use gnucash_ext::{ChartTemplate, TemplateImporter};
+///
+/// let template = ChartTemplate::from_file("acctchrt_common.gnucash-xea")?;
+/// let report = TemplateImporter::new(&book)
+///     .currency(&eur)
+///     .dry_run(true)
+///     .import(&template)?;
+/// for name in &report.created {
+///     println!("would create {}", name);
+/// }
+/// ```
+pub struct TemplateImporter<'a> {
+    book: &'a Book,
+    currency: Option<&'a Commodity<'a>>,
+    dry_run: bool,
+}
+
+/// A template account resolved against the book.
+struct PlannedAccount<'t, 'b> {
+    full_name: String,
+    template: &'t AccountTemplate,
+    account_type: GNCAccountType,
+    commodity: PlannedCommodity<'b>,
+    exists: bool,
+}
+
+/// The commodity a new account is given.
+enum PlannedCommodity<'b> {
+    /// The one it takes from its parent on creation, if any.
+    Inherit,
+    /// The importer's currency.
+    Currency,
+    /// A commodity named by the template.
+    Found(Commodity<'b>),
+}
+
+impl<'a> TemplateImporter<'a> {
+    /// Creates a new TemplateImporter.
+    pub fn new(book: &'a Book) -> Self {
+        Self {
+            book,
+            currency: None,
+            dry_run: false,
+        }
+    }
+
+    /// Sets the currency for the new accounts.
+    ///
+    /// It replaces every currency named in the template, as GnuCash's new
+    /// account hierarchy assistant does, and is given to new top-level
+    /// accounts that name no commodity. Other commodities, such as stocks,
+    /// are kept.
+    pub fn currency(mut self, currency: &'a Commodity<'a>) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Only reports what would be created, without changing the book.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Imports `template` into the book.
+    ///
+    /// # Errors
+    /// * [`Error::ReadOnly`] if the book is read-only.
+    /// * [`Error::InvalidAccountType`] for an unknown account type, or for
+    ///   `ROOT`.
+    /// * [`Error::InvalidOperation`] for an empty account name or one that
+    ///   contains the account separator, an unknown commodity, or an
+    ///   account that exists with a different type.
+    /// * Any error from creating or editing an account; accounts created
+    ///   before it are kept.
+    pub fn import(&self, template: &ChartTemplate) -> Result<ImportReport> {
+        if self.book.is_readonly() && !self.dry_run {
+            return Err(Error::ReadOnly);
+        }
+        let root = self.book.root_account().ok_or(Error::NullPointer("root account"))?;
+        let table = self
+            .book
+            .commodity_table()
+            .ok_or(Error::NullPointer("commodity table"))?;
+        let separator = Account::separator();
+
+        let mut plan = Vec::with_capacity(template.account_count());
+        let mut seen = HashSet::new();
+        for account in &template.accounts {
+            self.plan(&root, &table, &separator, "", account, &mut seen, &mut plan)?;
+        }
+
+        let mut report = ImportReport::default();
+        for planned in plan {
+            if planned.exists {
+                report.existing.push(planned.full_name);
+                continue;
+            }
+            if !self.dry_run {
+                let account = self.book.ensure_account(&planned.full_name, planned.account_type)?;
+                account.edit_with(|a| self.apply(a, &planned))?;
+            }
+            report.created.push(planned.full_name);
+        }
+        Ok(report)
+    }
+
+    /// Resolves `account` and its children, depth first.
+    #[allow(clippy::too_many_arguments)]
+    fn plan<'t>(
+        &self,
+        root: &Account<'a>,
+        table: &CommodityTable<'a>,
+        separator: &str,
+        parent_name: &str,
+        account: &'t AccountTemplate,
+        seen: &mut HashSet<String>,
+        plan: &mut Vec<PlannedAccount<'t, 'a>>,
+    ) -> Result<()> {
+        Account::check_name(&account.name)?;
+        let full_name = if parent_name.is_empty() {
+            account.name.clone()
+        } else {
+            format!("{}{}{}", parent_name, separator, account.name)
+        };
+        let account_type = parse_account_type(&account.account_type)?;
+
+        let existing = root.lookup_by_full_name(&full_name);
+        if let Some(existing) = &existing
+            && existing.account_type() != account_type
+        {
+            return Err(Error::InvalidOperation(format!(
+                "account {:?} exists with type {:?}, not {:?}",
+                full_name,
+                existing.account_type(),
+                account_type
+            )));
+        }
+
+        let commodity = match (&account.commodity, self.currency.is_some()) {
+            (Some(c), true) if c.is_currency() => PlannedCommodity::Currency,
+            (Some(c), false) if c.namespace == namespace::TEMPLATE => PlannedCommodity::Inherit,
+            (Some(c), _) => match table.lookup(c.engine_namespace(), &c.mnemonic) {
+                Some(found) => PlannedCommodity::Found(found),
+                None => {
+                    return Err(Error::InvalidOperation(format!(
+                        "unknown commodity {}:{} for account {:?}",
+                        c.namespace, c.mnemonic, full_name
+                    )));
+                }
+            },
+            (None, true) if parent_name.is_empty() => PlannedCommodity::Currency,
+            (None, _) => PlannedCommodity::Inherit,
+        };
+
+        // A name repeated in the template is created once
+        let exists = existing.is_some() || !seen.insert(full_name.clone());
+        plan.push(PlannedAccount {
+            full_name: full_name.clone(),
+            template: account,
+            account_type,
+            commodity,
+            exists,
+        });
+        for child in &account.children {
+            self.plan(root, table, separator, &full_name, child, seen, plan)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the template's attributes on a newly created account.
+    fn apply(&self, account: &Account, planned: &PlannedAccount) -> Result<()> {
+        let template = planned.template;
+        if let Some(code) = &template.code {
+            account.set_code(code)?;
+        }
+        if let Some(description) = &template.description {
+            account.set_description(description)?;
+        }
+        match (&planned.commodity, self.currency) {
+            (PlannedCommodity::Found(commodity), _) => account.set_commodity(commodity)?,
+            (PlannedCommodity::Currency, Some(currency)) => account.set_commodity(currency)?,
+            _ => {}
+        }
+        if template.placeholder {
+            account.set_placeholder(true)?;
+        }
+        if template.hidden {
+            account.set_hidden(true)?;
+        }
+        if template.tax_related {
+            account.set_tax_related(true)?;
+        }
+        if let Some(code) = &template.tax_code {
+            account.set_tax_us_code(code)?;
+        }
+        if let Some(source) = &template.tax_source {
+            account.set_tax_us_payer_name_source(source)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts an account type name such as `"BANK"` (in any case).
+///
+/// `ROOT` and names the engine does not know are rejected.
+fn parse_account_type(name: &str) -> Result<GNCAccountType> {
+    let c_name = CString::new(name.trim().to_ascii_uppercase())?;
+    let account_type = unsafe { ffi::xaccAccountStringToEnum(c_name.as_ptr()) };
+    let raw_type = account_type as i32;
+    if account_type == GNCAccountType::ACCT_TYPE_ROOT
+        || !(0..GNCAccountType::NUM_ACCOUNT_TYPES as i32).contains(&raw_type)
+    {
+        return Err(Error::InvalidAccountType(raw_type));
+    }
+    Ok(account_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template_account(name: &str, account_type: &str) -> AccountTemplate {
+        AccountTemplate {
+            name: name.to_string(),
+            account_type: account_type.to_string(),
+            ..AccountTemplate::default()
+        }
+    }
+
+    fn sample() -> ChartTemplate {
+        let mut assets = template_account("Assets", "ASSET");
+        assets.placeholder = true;
+        assets.commodity = Some(TemplateCommodity {
+            namespace: "ISO4217".to_string(),
+            mnemonic: "USD".to_string(),
+        });
+        let mut checking = template_account("Checking", "BANK");
+        checking.code = Some("1010".to_string());
+        assets.children.push(checking);
+
+        let mut taxes = template_account("Taxes", "EXPENSE");
+        taxes.hidden = true;
+        taxes.tax_related = true;
+        taxes.tax_code = Some("N261".to_string());
+        ChartTemplate {
+            title: Some("Sample".to_string()),
+            description: None,
+            accounts: vec![assets, taxes],
+        }
+    }
+
+    #[test]
+    fn test_import_creates_accounts() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let eur = book.commodity_table().unwrap().lookup_currency("EUR").unwrap();
+
+        let report = TemplateImporter::new(&book)
+            .currency(&eur)
+            .import(&sample())
+            .unwrap();
+        assert_eq!(report.created, ["Assets", "Assets:Checking", "Taxes"]);
+        assert!(report.existing.is_empty());
+
+        let root = book.root_account().unwrap();
+        let assets = root.lookup_by_full_name("Assets").unwrap();
+        assert!(assets.is_placeholder());
+        assert_eq!(assets.commodity().unwrap().mnemonic().as_deref(), Some("EUR"));
+        let checking = root.lookup_by_full_name("Assets:Checking").unwrap();
+        assert_eq!(checking.account_type(), GNCAccountType::ACCT_TYPE_BANK);
+        assert_eq!(checking.code().as_deref(), Some("1010"));
+        assert_eq!(checking.commodity().unwrap().mnemonic().as_deref(), Some("EUR"));
+        let taxes = root.lookup_by_full_name("Taxes").unwrap();
+        assert!(taxes.is_hidden() && taxes.is_tax_related());
+        assert_eq!(taxes.tax_us_code().as_deref(), Some("N261"));
+
+        // Importing again finds everything in place
+        let again = TemplateImporter::new(&book).import(&sample()).unwrap();
+        assert!(again.created.is_empty());
+        assert_eq!(again.existing.len(), 3);
+    }
+
+    #[test]
+    fn test_dry_run_changes_nothing() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        book.ensure_account("Taxes", GNCAccountType::ACCT_TYPE_EXPENSE)
+            .unwrap();
+
+        let report = TemplateImporter::new(&book)
+            .dry_run(true)
+            .import(&sample())
+            .unwrap();
+        assert_eq!(report.created, ["Assets", "Assets:Checking"]);
+        assert_eq!(report.existing, ["Taxes"]);
+        assert!(book.root_account().unwrap().lookup_by_full_name("Assets").is_none());
+    }
+
+    #[test]
+    fn test_import_rejects_bad_templates() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let mut bad_type = sample();
+        bad_type.accounts[1].children.push(template_account("Odd", "NOT-A-TYPE"));
+        assert!(matches!(
+            TemplateImporter::new(&book).import(&bad_type),
+            Err(Error::InvalidAccountType(_))
+        ));
+        // Nothing is created when the template is rejected
+        assert!(book.root_account().unwrap().lookup_by_full_name("Assets").is_none());
+
+        let mut bad_name = sample();
+        bad_name.accounts[0].name = format!("A{}B", Account::separator());
+        assert!(TemplateImporter::new(&book).import(&bad_name).is_err());
+
+        book.ensure_account("Taxes", GNCAccountType::ACCT_TYPE_LIABILITY)
+            .unwrap();
+        assert!(matches!(
+            TemplateImporter::new(&book).dry_run(true).import(&sample()),
+            Err(Error::InvalidOperation(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            TemplateFormat::from_path(Path::new("acctchrt_common.gnucash-xea")),
+            TemplateFormat::Xea
+        );
+        assert_eq!(
+            TemplateFormat::from_path(Path::new("chart.JSON")),
+            TemplateFormat::Json
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parse_json() {
+        let text = r#"{
+            "title": "Company",
+            "accounts": [{
+                "name": "Assets",
+                "type": "ASSET",
+                "placeholder": true,
+                "commodity": {"mnemonic": "USD"},
+                "children": [{"name": "Checking", "type": "BANK", "code": "1010"}]
+            }]
+        }"#;
+        let chart = ChartTemplate::parse(text, TemplateFormat::Json).unwrap();
+        assert_eq!(chart.title.as_deref(), Some("Company"));
+        assert_eq!(chart.account_count(), 2);
+        let assets = &chart.accounts[0];
+        assert!(assets.placeholder);
+        assert_eq!(assets.commodity.as_ref().unwrap().namespace, "CURRENCY");
+        assert_eq!(assets.children[0].code.as_deref(), Some("1010"));
+
+        let unknown_key = r#"{"accounts": [{"name": "A", "type": "BANK", "hiden": true}]}"#;
+        assert!(ChartTemplate::parse(unknown_key, TemplateFormat::Json).is_err());
+    }
+}
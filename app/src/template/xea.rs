@@ -0,0 +1,503 @@
+//! Reader for GnuCash `.gnucash-xea` account templates.
+//!
+//! An xea file is a `gnc-account-example` document holding a title, a
+//! description and a flat list of `gnc:account` elements in the format of
+//! the XML backend. Accounts point at their parent through `act:parent`,
+//! and the template's root account (type `ROOT`) is dropped, so its
+//! children become top-level accounts.
+//!
+//! The XML reader is deliberately small: it handles elements, attributes,
+//! text, CDATA, comments, processing instructions and the predefined and
+//! numeric entities; attributes are checked but not kept. Element names
+//! are matched with their prefixes as written (`act:name`), which is how
+//! GnuCash itself writes these files.
+
+use std::collections::{HashMap, HashSet};
+
+use gnucash_sys::{Error, Result};
+
+use super::{AccountTemplate, ChartTemplate, TemplateCommodity};
+
+/// Parses the contents of a `.gnucash-xea` file.
+pub(super) fn parse(text: &str) -> Result<ChartTemplate> {
+    let document = Reader::new(text).document()?;
+    if document.name != "gnc-account-example" {
+        return Err(Error::InvalidOperation(format!(
+            "not a GnuCash account template: root element is <{}>",
+            document.name
+        )));
+    }
+
+    let mut records = Vec::new();
+    for element in document.children_named("gnc:account") {
+        records.push(AccountRecord::from_element(element)?);
+    }
+
+    // Link accounts to their parents, keeping document order
+    let root_ids: HashSet<&str> = records
+        .iter()
+        .filter(|r| r.account_type == "ROOT")
+        .filter_map(|r| r.id.as_deref())
+        .collect();
+    let known_ids: HashSet<&str> = records.iter().filter_map(|r| r.id.as_deref()).collect();
+    let mut top_level = Vec::new();
+    let mut children: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, record) in records.iter().enumerate() {
+        if record.account_type == "ROOT" {
+            continue;
+        }
+        match record.parent.as_deref() {
+            Some(parent) if known_ids.contains(parent) && !root_ids.contains(parent) => {
+                children.entry(parent).or_default().push(index)
+            }
+            _ => top_level.push(index),
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let accounts = top_level
+        .iter()
+        .map(|&index| build_tree(&records, &children, index, &mut visited))
+        .collect();
+    Ok(ChartTemplate {
+        title: document.child_text("gnc-act:title"),
+        description: document.child_text("gnc-act:short-description"),
+        accounts,
+    })
+}
+
+fn build_tree(
+    records: &[AccountRecord],
+    children: &HashMap<&str, Vec<usize>>,
+    index: usize,
+    visited: &mut HashSet<usize>,
+) -> AccountTemplate {
+    visited.insert(index);
+    let record = &records[index];
+    let mut account = record.template.clone();
+    if let Some(child_indices) = record.id.as_deref().and_then(|id| children.get(id)) {
+        for &child in child_indices {
+            // A malformed file could make an account its own ancestor
+            if !visited.contains(&child) {
+                account.children.push(build_tree(records, children, child, visited));
+            }
+        }
+    }
+    account
+}
+
+/// A `gnc:account` element before it is placed in the tree.
+struct AccountRecord {
+    id: Option<String>,
+    parent: Option<String>,
+    account_type: String,
+    template: AccountTemplate,
+}
+
+impl AccountRecord {
+    fn from_element(element: &Element) -> Result<Self> {
+        let name = element
+            .child_text("act:name")
+            .ok_or_else(|| Error::InvalidOperation("template account has no name".to_string()))?;
+        let account_type = element.child_text("act:type").ok_or_else(|| {
+            Error::InvalidOperation(format!("template account {:?} has no type", name))
+        })?;
+        let commodity = element.child("act:commodity").and_then(|c| {
+            Some(TemplateCommodity {
+                namespace: c.child_text("cmdty:space")?,
+                mnemonic: c.child_text("cmdty:id")?,
+            })
+        });
+
+        let mut template = AccountTemplate {
+            name,
+            account_type: account_type.clone(),
+            code: element.child_text("act:code"),
+            description: element.child_text("act:description"),
+            commodity,
+            ..AccountTemplate::default()
+        };
+        if let Some(slots) = element.child("act:slots") {
+            for (key, value) in slots.slots() {
+                match key.as_str() {
+                    "placeholder" => template.placeholder = is_true(&value.text),
+                    "hidden" => template.hidden = is_true(&value.text),
+                    "tax-related" => template.tax_related = is_true(&value.text),
+                    "tax-US" => {
+                        for (key, value) in value.slots() {
+                            match key.as_str() {
+                                "code" => template.tax_code = value.text_value(),
+                                "payer-name-source" => template.tax_source = value.text_value(),
+                                _ => {}
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Self {
+            id: element.child_text("act:id"),
+            parent: element.child_text("act:parent"),
+            account_type,
+            template,
+        })
+    }
+}
+
+/// Reads a boolean slot, which templates store as the string `"true"` or
+/// the integer `1`.
+fn is_true(text: &str) -> bool {
+    matches!(text.trim(), "true" | "1")
+}
+
+// ==================== XML ====================
+
+/// An XML element with its child elements and text.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'e>(&'e self, name: &'e str) -> impl Iterator<Item = &'e Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Returns the trimmed text of the first child named `name`, or `None`
+    /// if it is missing or blank.
+    fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name).and_then(Element::text_value)
+    }
+
+    /// Returns the trimmed text, or `None` if it is blank.
+    fn text_value(&self) -> Option<String> {
+        let text = self.text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    /// Returns the `slot:key` and `slot:value` of each `slot` child.
+    fn slots(&self) -> impl Iterator<Item = (String, &Element)> {
+        self.children_named("slot").filter_map(|slot| {
+            Some((slot.child_text("slot:key")?, slot.child("slot:value")?))
+        })
+    }
+}
+
+struct Reader<'t> {
+    text: &'t str,
+    pos: usize,
+}
+
+impl<'t> Reader<'t> {
+    fn new(text: &'t str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    /// Reads the document element, skipping the prolog around it.
+    fn document(&mut self) -> Result<Element> {
+        self.skip_misc()?;
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected an element"));
+        }
+        let element = self.element()?;
+        self.skip_misc()?;
+        if !self.rest().is_empty() {
+            return Err(self.error("content after the document element"));
+        }
+        Ok(element)
+    }
+
+    /// Skips whitespace, comments, processing instructions and doctypes.
+    fn skip_misc(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!DOCTYPE") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn element(&mut self) -> Result<Element> {
+        self.pos += 1; // '<'
+        let mut element = Element {
+            name: self.name()?,
+            ..Element::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            self.quoted()?;
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(self.error(&format!(
+                        "expected </{}>, found </{}>",
+                        element.name, name
+                    )));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").ok_or_else(|| self.error("unterminated CDATA"))?;
+                element.text.push_str(&cdata[..end]);
+                self.pos += "<![CDATA[".len() + end + "]]>".len();
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                element.children.push(self.element()?);
+            } else if rest.is_empty() {
+                return Err(self.error(&format!("unclosed <{}>", element.name)));
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                element.text.push_str(&self.unescape(&rest[..end])?);
+                self.pos += end;
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += end;
+        Ok(rest[..end].to_string())
+    }
+
+    fn quoted(&mut self) -> Result<()> {
+        let rest = self.rest();
+        let quote = match rest.chars().next() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => return Err(self.error("expected a quoted attribute value")),
+        };
+        let end = rest[1..]
+            .find(quote)
+            .ok_or_else(|| self.error("unterminated attribute value"))?;
+        self.unescape(&rest[1..=end])?;
+        self.pos += end + 2;
+        Ok(())
+    }
+
+    /// Replaces entity and character references in `raw`.
+    fn unescape(&self, raw: &str) -> Result<String> {
+        let mut out = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(amp) = rest.find('&') {
+            out.push_str(&rest[..amp]);
+            let semi = rest[amp..]
+                .find(';')
+                .ok_or_else(|| self.error("unterminated entity reference"))?;
+            let entity = &rest[amp + 1..amp + semi];
+            let c = match entity {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => entity
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(|code| code.ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error(&format!("unknown entity &{};", entity)))?,
+            };
+            out.push(c);
+            rest = &rest[amp + semi + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {:?}", token)))
+        }
+    }
+
+    fn skip_past(&mut self, token: &str) -> Result<()> {
+        let end = self
+            .rest()
+            .find(token)
+            .ok_or_else(|| self.error(&format!("missing {:?}", token)))?;
+        self.pos += end + token.len();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> &'t str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        Error::InvalidOperation(format!("invalid template, line {}: {}", line, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- A cut-down chart of accounts -->
+<gnc-account-example
+  xmlns="http://www.gnucash.org/XML/"
+  xmlns:act="http://www.gnucash.org/XML/act">
+  <gnc-act:title>
+    Sample Accounts
+  </gnc-act:title>
+  <gnc-act:short-description>Accounts &amp; more</gnc-act:short-description>
+  <gnc:account version="2.0.0">
+    <act:name>Root Account</act:name>
+    <act:id type="new">root</act:id>
+    <act:type>ROOT</act:type>
+  </gnc:account>
+  <gnc:account version="2.0.0">
+    <act:name>Expenses</act:name>
+    <act:id type="new">exp</act:id>
+    <act:type>EXPENSE</act:type>
+    <act:commodity>
+      <cmdty:space>ISO4217</cmdty:space>
+      <cmdty:id>USD</cmdty:id>
+    </act:commodity>
+    <act:slots>
+      <slot>
+        <slot:key>placeholder</slot:key>
+        <slot:value type="string">true</slot:value>
+      </slot>
+    </act:slots>
+    <act:parent type="new">root</act:parent>
+  </gnc:account>
+  <gnc:account version="2.0.0">
+    <act:name>Taxes</act:name>
+    <act:id type="new">tax</act:id>
+    <act:type>EXPENSE</act:type>
+    <act:code>6100</act:code>
+    <act:description><![CDATA[Federal <and> state]]></act:description>
+    <act:slots>
+      <slot>
+        <slot:key>hidden</slot:key>
+        <slot:value type="string">true</slot:value>
+      </slot>
+      <slot>
+        <slot:key>tax-related</slot:key>
+        <slot:value type="integer">1</slot:value>
+      </slot>
+      <slot>
+        <slot:key>tax-US</slot:key>
+        <slot:value type="frame">
+          <slot>
+            <slot:key>code</slot:key>
+            <slot:value type="string">N261</slot:value>
+          </slot>
+          <slot>
+            <slot:key>payer-name-source</slot:key>
+            <slot:value type="string">current</slot:value>
+          </slot>
+        </slot:value>
+      </slot>
+    </act:slots>
+    <act:parent type="new">exp</act:parent>
+  </gnc:account>
+  <gnc:account version="2.0.0">
+    <act:name>Income</act:name>
+    <act:id type="new">inc</act:id>
+    <act:type>INCOME</act:type>
+    <act:parent type="new">root</act:parent>
+  </gnc:account>
+</gnc-account-example>
+"#;
+
+    #[test]
+    fn test_parse_xea() {
+        let chart = parse(SAMPLE).unwrap();
+        assert_eq!(chart.title.as_deref(), Some("Sample Accounts"));
+        assert_eq!(chart.description.as_deref(), Some("Accounts & more"));
+        assert_eq!(chart.accounts.len(), 2);
+
+        let expenses = &chart.accounts[0];
+        assert_eq!(expenses.name, "Expenses");
+        assert_eq!(expenses.account_type, "EXPENSE");
+        assert!(expenses.placeholder);
+        assert_eq!(
+            expenses.commodity,
+            Some(TemplateCommodity {
+                namespace: "ISO4217".to_string(),
+                mnemonic: "USD".to_string(),
+            })
+        );
+
+        let taxes = &expenses.children[0];
+        assert_eq!(taxes.code.as_deref(), Some("6100"));
+        assert_eq!(taxes.description.as_deref(), Some("Federal <and> state"));
+        assert!(taxes.hidden && taxes.tax_related && !taxes.placeholder);
+        assert_eq!(taxes.tax_code.as_deref(), Some("N261"));
+        assert_eq!(taxes.tax_source.as_deref(), Some("current"));
+        assert_eq!(chart.accounts[1].name, "Income");
+    }
+
+    #[test]
+    fn test_reader_entities() {
+        let element = Reader::new("<a x='1 &lt; 2' y=\"/>\">&#65;&#x42; t&apos;s<b/></a>")
+            .document()
+            .unwrap();
+        assert_eq!(element.text, "AB t's");
+        assert_eq!(element.children[0].name, "b");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("<gnc-account-example><a></b></gnc-account-example>").is_err());
+        assert!(parse("<gnc-account-example>&bogus;</gnc-account-example>").is_err());
+        assert!(parse("<gnc-v2></gnc-v2>").is_err());
+        let nameless = "<gnc-account-example><gnc:account><act:type>BANK</act:type>\
+                        </gnc:account></gnc-account-example>";
+        assert!(matches!(parse(nameless), Err(Error::InvalidOperation(_))));
+    }
+}
@@ -42,6 +42,11 @@ fn main() {
     // Link against gnucash engine library
     println!("cargo:rustc-link-lib=gnc-engine");
 
+    // SQLite lock inspection reads the gnclock table directly
+    if env::var("CARGO_FEATURE_SQLITE").is_ok() {
+        println!("cargo:rustc-link-lib=sqlite3");
+    }
+
     // Build bindgen bindings
     let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
     let mut builder = bindgen::Builder::default()
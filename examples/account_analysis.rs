@@ -12,7 +12,7 @@
 
 use std::env;
 
-use gnucash_sys::{init_engine, Account, Numeric, Session, SessionOpenMode};
+use gnucash_sys::{init_engine, Account, Numeric, Session, SessionOpenMode, Time64};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -66,7 +66,7 @@ fn main() {
     }
 }
 
-fn find_account_by_path(root: &Account, path: &str) -> Option<Account> {
+fn find_account_by_path<'a>(root: &Account<'a>, path: &str) -> Option<Account<'a>> {
     let parts: Vec<&str> = path.split(':').collect();
     let mut current = root.clone_ref();
 
@@ -182,17 +182,11 @@ fn add_numeric(a: &Numeric, b: &Numeric) -> Numeric {
     Numeric::new(a_scaled + b_scaled, denom)
 }
 
-fn format_date(timestamp: i64) -> String {
-    if timestamp == 0 {
+fn format_date(time: Time64) -> String {
+    if time.secs() == 0 {
         return "N/A".to_string();
     }
-    // Simple date formatting - in production use chrono
-    let days_since_epoch = timestamp / 86400;
-    let years = days_since_epoch / 365 + 1970;
-    let remaining_days = days_since_epoch % 365;
-    let month = remaining_days / 30 + 1;
-    let day = remaining_days % 30 + 1;
-    format!("{:04}-{:02}-{:02}", years, month.min(12), day.min(28))
+    time.date().to_string()
 }
 
 fn truncate(s: &str, max_len: usize) -> String {
@@ -226,7 +220,7 @@ trait CloneRef {
     fn clone_ref(&self) -> Self;
 }
 
-impl CloneRef for Account {
+impl CloneRef for Account<'_> {
     fn clone_ref(&self) -> Self {
         unsafe { Account::from_raw(self.as_ptr(), false).unwrap() }
     }
@@ -4,9 +4,11 @@
 //! - A debit to an expense account
 //! - A credit from a bank account
 
-use gnucash_sys::{init_engine, Account, Book, GNCAccountType, Numeric, Split, Transaction};
+use gnucash_sys::{
+    init_engine, Account, Book, GNCAccountType, GncDate, Numeric, Result, Split, Transaction,
+};
 
-fn main() {
+fn main() -> Result<()> {
     // Initialize the GnuCash engine
     init_engine();
 
@@ -19,12 +21,12 @@ fn main() {
     let root = book.root_account().expect("Book should have root account");
 
     // Create Assets:Bank account
-    let assets = create_account(&book, &root, "Assets", GNCAccountType::ACCT_TYPE_ASSET);
-    let bank = create_account(&book, &assets, "Checking", GNCAccountType::ACCT_TYPE_BANK);
+    let assets = create_account(&book, &root, "Assets", GNCAccountType::ACCT_TYPE_ASSET)?;
+    let bank = create_account(&book, &assets, "Checking", GNCAccountType::ACCT_TYPE_BANK)?;
 
     // Create Expenses:Groceries account
-    let expenses = create_account(&book, &root, "Expenses", GNCAccountType::ACCT_TYPE_EXPENSE);
-    let groceries = create_account(&book, &expenses, "Groceries", GNCAccountType::ACCT_TYPE_EXPENSE);
+    let expenses = create_account(&book, &root, "Expenses", GNCAccountType::ACCT_TYPE_EXPENSE)?;
+    let groceries = create_account(&book, &expenses, "Groceries", GNCAccountType::ACCT_TYPE_EXPENSE)?;
 
     println!("Created accounts:");
     println!("  Assets:Checking");
@@ -37,34 +39,33 @@ fn main() {
     let txn = Transaction::new(&book);
     txn.begin_edit();
 
-    txn.set_description("Weekly grocery shopping");
-    txn.set_num("1001");
-    txn.set_notes("Bought food for the week");
+    txn.set_description("Weekly grocery shopping")?;
+    txn.set_num("1001")?;
+    txn.set_notes("Bought food for the week")?;
 
-    // Set the date (using Unix timestamp - Jan 15, 2024)
-    // In production, use proper date handling
-    txn.set_date(15, 1, 2024);
+    // Set the date (Jan 15, 2024)
+    txn.set_date(GncDate::from_dmy(15, 1, 2024).unwrap())?;
 
     // Create the expense split (debit - positive in expense account)
     let expense_split = Split::new(&book);
-    expense_split.set_account(&groceries);
-    expense_split.set_transaction(&txn);
-    expense_split.set_memo("Groceries");
+    expense_split.set_account(&groceries)?;
+    expense_split.set_transaction(&txn)?;
+    expense_split.set_memo("Groceries")?;
 
     // $50.00 = 5000 cents / 100
     let amount = Numeric::new(5000, 100);
-    expense_split.set_amount(amount);
-    expense_split.set_value(amount);
+    expense_split.set_amount(amount)?;
+    expense_split.set_value(amount)?;
 
     // Create the bank split (credit - negative from bank account)
     let bank_split = Split::new(&book);
-    bank_split.set_account(&bank);
-    bank_split.set_transaction(&txn);
-    bank_split.set_memo("Debit card");
+    bank_split.set_account(&bank)?;
+    bank_split.set_transaction(&txn)?;
+    bank_split.set_memo("Debit card")?;
 
     let neg_amount = Numeric::new(-5000, 100);
-    bank_split.set_amount(neg_amount);
-    bank_split.set_value(neg_amount);
+    bank_split.set_amount(neg_amount)?;
+    bank_split.set_value(neg_amount)?;
 
     txn.commit_edit();
 
@@ -113,20 +114,21 @@ fn main() {
     std::mem::forget(expenses);
     std::mem::forget(bank);
     std::mem::forget(assets);
+    Ok(())
 }
 
-fn create_account(
-    book: &Book,
+fn create_account<'a>(
+    book: &'a Book,
     parent: &Account,
     name: &str,
     account_type: GNCAccountType,
-) -> Account {
+) -> Result<Account<'a>> {
     let mut account = Account::new(book);
     account.begin_edit();
-    account.set_name(name);
-    account.set_type(account_type);
+    account.set_name(name)?;
+    account.set_type(account_type)?;
     account.commit_edit();
     parent.append_child(&account);
     account.mark_unowned();
-    account
+    Ok(account)
 }
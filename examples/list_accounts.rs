@@ -53,6 +53,10 @@ fn print_account(account: &Account, depth: usize) {
     let name = account.name().unwrap_or_else(|| "(root)".to_string());
     let account_type = account.account_type();
     let balance = account.balance();
+    let commodity = account
+        .commodity()
+        .and_then(|c| c.mnemonic())
+        .unwrap_or_default();
 
     // Format balance for display
     let balance_str = if balance.denom() > 0 {
@@ -85,11 +89,12 @@ fn print_account(account: &Account, depth: usize) {
         println!("{}{}", indent, name);
     } else {
         println!(
-            "{}{:<30} {:>8} {:>12}",
+            "{}{:<30} {:>8} {:>12} {}",
             indent,
             truncate(&name, 30 - depth * 2),
             type_abbrev,
-            balance_str
+            balance_str,
+            commodity
         );
     }
 
@@ -6,10 +6,10 @@
 //! Based on: gnucash/bindings/python/example_scripts/new_book_with_opening_balances.py
 
 use gnucash_sys::{
-    init_engine, Account, Book, GNCAccountType, Numeric, Split, Transaction,
+    init_engine, Account, Book, GNCAccountType, GncDate, Numeric, Result, Split, Transaction,
 };
 
-fn main() {
+fn main() -> Result<()> {
     init_engine();
 
     println!("Creating book with opening balances...\n");
@@ -21,31 +21,31 @@ fn main() {
     println!("Creating chart of accounts...");
 
     // Assets
-    let assets = create_account(&book, &root, "Assets", GNCAccountType::ACCT_TYPE_ASSET);
-    let current = create_account(&book, &assets, "Current Assets", GNCAccountType::ACCT_TYPE_ASSET);
-    let checking = create_account(&book, &current, "Checking", GNCAccountType::ACCT_TYPE_BANK);
-    let savings = create_account(&book, &current, "Savings", GNCAccountType::ACCT_TYPE_BANK);
-    let cash = create_account(&book, &current, "Cash on Hand", GNCAccountType::ACCT_TYPE_CASH);
+    let assets = create_account(&book, &root, "Assets", GNCAccountType::ACCT_TYPE_ASSET)?;
+    let current = create_account(&book, &assets, "Current Assets", GNCAccountType::ACCT_TYPE_ASSET)?;
+    let checking = create_account(&book, &current, "Checking", GNCAccountType::ACCT_TYPE_BANK)?;
+    let savings = create_account(&book, &current, "Savings", GNCAccountType::ACCT_TYPE_BANK)?;
+    let cash = create_account(&book, &current, "Cash on Hand", GNCAccountType::ACCT_TYPE_CASH)?;
 
-    let fixed = create_account(&book, &assets, "Fixed Assets", GNCAccountType::ACCT_TYPE_ASSET);
-    let _equipment = create_account(&book, &fixed, "Equipment", GNCAccountType::ACCT_TYPE_ASSET);
+    let fixed = create_account(&book, &assets, "Fixed Assets", GNCAccountType::ACCT_TYPE_ASSET)?;
+    let _equipment = create_account(&book, &fixed, "Equipment", GNCAccountType::ACCT_TYPE_ASSET)?;
 
     // Liabilities
-    let liabilities = create_account(&book, &root, "Liabilities", GNCAccountType::ACCT_TYPE_LIABILITY);
-    let credit_card = create_account(&book, &liabilities, "Credit Card", GNCAccountType::ACCT_TYPE_CREDIT);
+    let liabilities = create_account(&book, &root, "Liabilities", GNCAccountType::ACCT_TYPE_LIABILITY)?;
+    let credit_card = create_account(&book, &liabilities, "Credit Card", GNCAccountType::ACCT_TYPE_CREDIT)?;
 
     // Equity
-    let equity = create_account(&book, &root, "Equity", GNCAccountType::ACCT_TYPE_EQUITY);
-    let opening = create_account(&book, &equity, "Opening Balances", GNCAccountType::ACCT_TYPE_EQUITY);
+    let equity = create_account(&book, &root, "Equity", GNCAccountType::ACCT_TYPE_EQUITY)?;
+    let opening = create_account(&book, &equity, "Opening Balances", GNCAccountType::ACCT_TYPE_EQUITY)?;
 
     // Income (no opening balance needed)
-    let income = create_account(&book, &root, "Income", GNCAccountType::ACCT_TYPE_INCOME);
-    let _salary = create_account(&book, &income, "Salary", GNCAccountType::ACCT_TYPE_INCOME);
+    let income = create_account(&book, &root, "Income", GNCAccountType::ACCT_TYPE_INCOME)?;
+    let _salary = create_account(&book, &income, "Salary", GNCAccountType::ACCT_TYPE_INCOME)?;
 
     // Expenses (no opening balance needed)
-    let expenses = create_account(&book, &root, "Expenses", GNCAccountType::ACCT_TYPE_EXPENSE);
-    let _groceries = create_account(&book, &expenses, "Groceries", GNCAccountType::ACCT_TYPE_EXPENSE);
-    let _utilities = create_account(&book, &expenses, "Utilities", GNCAccountType::ACCT_TYPE_EXPENSE);
+    let expenses = create_account(&book, &root, "Expenses", GNCAccountType::ACCT_TYPE_EXPENSE)?;
+    let _groceries = create_account(&book, &expenses, "Groceries", GNCAccountType::ACCT_TYPE_EXPENSE)?;
+    let _utilities = create_account(&book, &expenses, "Utilities", GNCAccountType::ACCT_TYPE_EXPENSE)?;
 
     println!("Created {} accounts", count_accounts(&root));
 
@@ -60,7 +60,7 @@ fn main() {
     println!("\nCreating opening balance transactions...");
 
     for (account, balance_cents) in balances {
-        create_opening_balance(&book, account, &opening, balance_cents);
+        create_opening_balance(&book, account, &opening, balance_cents)?;
 
         let name = account.name().unwrap();
         let balance_dollars = balance_cents as f64 / 100.0;
@@ -93,22 +93,23 @@ fn main() {
     std::mem::forget(cash);
     std::mem::forget(credit_card);
     std::mem::forget(opening);
+    Ok(())
 }
 
-fn create_account(
-    book: &Book,
+fn create_account<'a>(
+    book: &'a Book,
     parent: &Account,
     name: &str,
     account_type: GNCAccountType,
-) -> Account {
+) -> Result<Account<'a>> {
     let mut account = Account::new(book);
     account.begin_edit();
-    account.set_name(name);
-    account.set_type(account_type);
+    account.set_name(name)?;
+    account.set_type(account_type)?;
     account.commit_edit();
     parent.append_child(&account);
     account.mark_unowned();
-    account
+    Ok(account)
 }
 
 fn create_opening_balance(
@@ -116,35 +117,36 @@ fn create_opening_balance(
     account: &Account,
     equity_account: &Account,
     amount_cents: i64,
-) {
+) -> Result<()> {
     let txn = Transaction::new(book);
     txn.begin_edit();
 
     let account_name = account.name().unwrap_or_default();
-    txn.set_description(&format!("Opening Balance - {}", account_name));
-    txn.set_date(1, 1, 2024); // January 1, 2024
+    txn.set_description(&format!("Opening Balance - {}", account_name))?;
+    txn.set_date(GncDate::from_dmy(1, 1, 2024).unwrap())?; // January 1, 2024
 
     // Create split for the account
     let account_split = Split::new(book);
-    account_split.set_account(account);
-    account_split.set_transaction(&txn);
+    account_split.set_account(account)?;
+    account_split.set_transaction(&txn)?;
     let amount = Numeric::new(amount_cents, 100);
-    account_split.set_amount(amount);
-    account_split.set_value(amount);
+    account_split.set_amount(amount)?;
+    account_split.set_value(amount)?;
 
     // Create balancing split for equity
     let equity_split = Split::new(book);
-    equity_split.set_account(equity_account);
-    equity_split.set_transaction(&txn);
+    equity_split.set_account(equity_account)?;
+    equity_split.set_transaction(&txn)?;
     let neg_amount = Numeric::new(-amount_cents, 100);
-    equity_split.set_amount(neg_amount);
-    equity_split.set_value(neg_amount);
+    equity_split.set_amount(neg_amount)?;
+    equity_split.set_value(neg_amount)?;
 
     txn.commit_edit();
 
     std::mem::forget(account_split);
     std::mem::forget(equity_split);
     std::mem::forget(txn);
+    Ok(())
 }
 
 fn count_accounts(account: &Account) -> usize {
@@ -7,9 +7,9 @@
 //!
 //! Based on: gnucash/bindings/python/example_scripts/price_database_example.py
 
-use gnucash_sys::{init_engine, Book, Numeric, Price, PriceDB, PriceSource};
+use gnucash_sys::{init_engine, Book, Numeric, Price, PriceDB, PriceSource, Result, Time64};
 
-fn main() {
+fn main() -> Result<()> {
     init_engine();
 
     println!("Price Database Example\n");
@@ -17,7 +17,7 @@ fn main() {
     let book = Book::new();
 
     // Get or create the price database
-    let pricedb = PriceDB::get_db(&book).expect("Failed to get price database");
+    let pricedb = PriceDB::get(&book).expect("Failed to get price database");
 
     println!("Created price database");
 
@@ -29,10 +29,10 @@ fn main() {
     // Add price: 1 USD = 0.85 EUR on Jan 1, 2024
     let price1 = Price::new(&book);
     price1.begin_edit();
-    price1.set_time(1704067200); // Jan 1, 2024 00:00:00 UTC
-    price1.set_source(PriceSource::PRICE_SOURCE_USER_PRICE);
-    price1.set_type_string("last");
-    price1.set_value(Numeric::new(85, 100)); // 0.85
+    price1.set_time(Time64::from_secs(1704067200))?; // Jan 1, 2024 00:00:00 UTC
+    price1.set_source(PriceSource::PRICE_SOURCE_USER_PRICE)?;
+    price1.set_type_string("last")?;
+    price1.set_value(Numeric::new(85, 100))?; // 0.85
     price1.commit_edit();
 
     if pricedb.add_price(&price1) {
@@ -42,10 +42,10 @@ fn main() {
     // Add price: 1 USD = 0.84 EUR on Jan 15, 2024
     let price2 = Price::new(&book);
     price2.begin_edit();
-    price2.set_time(1705276800); // Jan 15, 2024 00:00:00 UTC
-    price2.set_source(PriceSource::PRICE_SOURCE_USER_PRICE);
-    price2.set_type_string("last");
-    price2.set_value(Numeric::new(84, 100)); // 0.84
+    price2.set_time(Time64::from_secs(1705276800))?; // Jan 15, 2024 00:00:00 UTC
+    price2.set_source(PriceSource::PRICE_SOURCE_USER_PRICE)?;
+    price2.set_type_string("last")?;
+    price2.set_value(Numeric::new(84, 100))?; // 0.84
     price2.commit_edit();
 
     if pricedb.add_price(&price2) {
@@ -55,10 +55,10 @@ fn main() {
     // Add price: 1 USD = 0.86 EUR on Feb 1, 2024
     let price3 = Price::new(&book);
     price3.begin_edit();
-    price3.set_time(1706745600); // Feb 1, 2024 00:00:00 UTC
-    price3.set_source(PriceSource::PRICE_SOURCE_FQ);
-    price3.set_type_string("last");
-    price3.set_value(Numeric::new(86, 100)); // 0.86
+    price3.set_time(Time64::from_secs(1706745600))?; // Feb 1, 2024 00:00:00 UTC
+    price3.set_source(PriceSource::PRICE_SOURCE_FQ)?;
+    price3.set_type_string("last")?;
+    price3.set_value(Numeric::new(86, 100))?; // 0.86
     price3.commit_edit();
 
     if pricedb.add_price(&price3) {
@@ -100,6 +100,7 @@ fn main() {
     // Clean up
     std::mem::forget(price1);
     std::mem::forget(price3);
+    Ok(())
 }
 
 fn display_price(price: &Price, label: &str) {
@@ -109,7 +110,7 @@ fn display_price(price: &Price, label: &str) {
     let type_str = price.type_string().unwrap_or_else(|| "N/A".to_string());
 
     println!("{}:", label);
-    println!("  Time:   {} (Unix timestamp)", time);
+    println!("  Time:   {}", time);
     println!("  Value:  {}", value);
     println!("  Source: {:?}", source);
     println!("  Type:   {}", type_str);
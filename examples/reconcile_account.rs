@@ -9,7 +9,7 @@
 
 use std::env;
 
-use gnucash_sys::{init_engine, Account, Session, SessionOpenMode};
+use gnucash_sys::{init_engine, Account, Session, SessionOpenMode, Time64};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -53,7 +53,7 @@ fn main() {
     }
 }
 
-fn find_account_by_path(root: &Account, path: &str) -> Option<Account> {
+fn find_account_by_path<'a>(root: &Account<'a>, path: &str) -> Option<Account<'a>> {
     let parts: Vec<&str> = path.split(':').collect();
     let mut current = unsafe { Account::from_raw(root.as_ptr(), false)? };
 
@@ -179,16 +179,11 @@ fn show_reconciliation_status(account: &Account) {
              reconciled.to_f64() + cleared_total);
 }
 
-fn format_date(timestamp: i64) -> String {
-    if timestamp == 0 {
+fn format_date(time: Time64) -> String {
+    if time.secs() == 0 {
         return "N/A".to_string();
     }
-    let days = timestamp / 86400;
-    let years = 1970 + days / 365;
-    let day_of_year = days % 365;
-    let month = day_of_year / 30 + 1;
-    let day = day_of_year % 30 + 1;
-    format!("{:04}-{:02}-{:02}", years, month.min(12), day.min(28))
+    time.date().to_string()
 }
 
 fn truncate(s: &str, max_len: usize) -> String {
@@ -7,7 +7,7 @@
 
 use std::env;
 
-use gnucash_sys::{init_engine, Account, Session, SessionOpenMode, Split};
+use gnucash_sys::{init_engine, Account, Session, SessionOpenMode, Split, Time64};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -124,7 +124,7 @@ fn list_recent_transactions(root: &Account, limit: usize) {
 }
 
 struct TransactionInfo {
-    date: i64,
+    date: Time64,
     account: String,
     description: String,
     amount: f64,
@@ -197,16 +197,11 @@ fn split_matches_term(split: &Split, term: &str) -> bool {
     false
 }
 
-fn format_date(timestamp: i64) -> String {
-    if timestamp == 0 {
+fn format_date(time: Time64) -> String {
+    if time.secs() == 0 {
         return "N/A".to_string();
     }
-    let days = timestamp / 86400;
-    let years = 1970 + days / 365;
-    let day_of_year = days % 365;
-    let month = day_of_year / 30 + 1;
-    let day = day_of_year % 30 + 1;
-    format!("{:04}-{:02}-{:02}", years, month.min(12), day.min(28))
+    time.date().to_string()
 }
 
 fn truncate(s: &str, max_len: usize) -> String {
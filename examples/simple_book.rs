@@ -13,9 +13,9 @@
 //!
 //! Based on: gnucash/bindings/python/example_scripts/simple_book.py
 
-use gnucash_sys::{init_engine, Account, Book, GNCAccountType};
+use gnucash_sys::{init_engine, Account, Book, GNCAccountType, Result};
 
-fn main() {
+fn main() -> Result<()> {
     // Initialize the GnuCash engine
     init_engine();
 
@@ -39,8 +39,8 @@ fn main() {
     for (name, account_type) in accounts {
         let mut account = Account::new(&book);
         account.begin_edit();
-        account.set_name(name);
-        account.set_type(account_type);
+        account.set_name(name)?;
+        account.set_type(account_type)?;
         account.commit_edit();
         root.append_child(&account);
         account.mark_unowned(); // Book now owns it
@@ -52,8 +52,8 @@ fn main() {
         // Create Current Assets
         let mut current_assets = Account::new(&book);
         current_assets.begin_edit();
-        current_assets.set_name("Current Assets");
-        current_assets.set_type(GNCAccountType::ACCT_TYPE_ASSET);
+        current_assets.set_name("Current Assets")?;
+        current_assets.set_type(GNCAccountType::ACCT_TYPE_ASSET)?;
         current_assets.commit_edit();
         assets.append_child(&current_assets);
         current_assets.mark_unowned();
@@ -61,9 +61,9 @@ fn main() {
         // Create Checking Account under Current Assets
         let mut checking = Account::new(&book);
         checking.begin_edit();
-        checking.set_name("Checking Account");
-        checking.set_type(GNCAccountType::ACCT_TYPE_BANK);
-        checking.set_description("Primary checking account");
+        checking.set_name("Checking Account")?;
+        checking.set_type(GNCAccountType::ACCT_TYPE_BANK)?;
+        checking.set_description("Primary checking account")?;
         checking.commit_edit();
         current_assets.append_child(&checking);
         checking.mark_unowned();
@@ -75,8 +75,8 @@ fn main() {
     if let Some(equity) = root.lookup_by_name("Equity") {
         let mut opening = Account::new(&book);
         opening.begin_edit();
-        opening.set_name("Opening Balances");
-        opening.set_type(GNCAccountType::ACCT_TYPE_EQUITY);
+        opening.set_name("Opening Balances")?;
+        opening.set_type(GNCAccountType::ACCT_TYPE_EQUITY)?;
         opening.commit_edit();
         equity.append_child(&opening);
         opening.mark_unowned();
@@ -91,6 +91,7 @@ fn main() {
     println!("\nBook created successfully!");
     println!("Note: This example creates an in-memory book.");
     println!("To persist to a file, use Session with a file URI.");
+    Ok(())
 }
 
 fn print_account_tree(account: &Account, depth: usize) {
@@ -63,7 +63,9 @@ fn create_new_file(uri: &str) {
                 // Get root account
                 if let Some(root) = book.root_account() {
                     root.begin_edit();
-                    root.set_description("Created by Rust gnucash-sys");
+                    root
+                        .set_description("Created by Rust gnucash-sys")
+                        .expect("Failed to set description");
                     root.commit_edit();
                     println!("Set root account description");
                 }
@@ -73,17 +75,16 @@ fn create_new_file(uri: &str) {
             }
 
             // Save and end session
-            if session.save().is_ok() {
-                println!("Session saved!");
-            } else {
-                eprintln!("Failed to save session");
+            match session.save() {
+                Ok(()) => println!("Session saved!"),
+                Err(e) => eprintln!("Failed to save session: {}", e),
             }
 
             session.end();
             println!("Session ended!");
         }
         Err(e) => {
-            eprintln!("Failed to create session: {:?}", e);
+            eprintln!("Failed to create session: {}", e);
             std::process::exit(1);
         }
     }
@@ -117,12 +118,14 @@ fn open_existing_file(path: &str) {
             println!("\nSession ended!");
         }
         Err(e) => {
-            eprintln!("Failed to open session: {:?}", e);
-            eprintln!();
-            eprintln!("Common errors:");
-            eprintln!("  - File not found");
-            eprintln!("  - File is locked by another process");
-            eprintln!("  - Invalid file format");
+            eprintln!("Failed to open session: {}", e);
+            if e.is_file_not_found() {
+                eprintln!("Check that the path is correct.");
+            } else if e.is_locked() {
+                eprintln!("The file is locked by another process.");
+            } else if e.is_too_new() {
+                eprintln!("The file was written by a newer version of GnuCash.");
+            }
             std::process::exit(1);
         }
     }
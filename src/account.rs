@@ -1,11 +1,12 @@
 //! Safe wrapper for GnuCash Account.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use crate::ffi;
+use crate::{edit, ffi};
 use crate::iter::{AccountChildren, AccountDescendants, AccountSplits};
-use crate::{Book, Guid, Numeric};
+use crate::{Book, Commodity, Editable, Error, Guid, Numeric, Result, Time64};
 
 /// Account type enumeration.
 pub use crate::ffi::GNCAccountType;
@@ -14,20 +15,24 @@ pub use crate::ffi::GNCAccountType;
 ///
 /// Accounts are organized in a tree hierarchy and hold splits
 /// denominated in a specific commodity.
-pub struct Account {
+pub struct Account<'book> {
     ptr: NonNull<ffi::Account>,
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Account {}
+/// Kept for compatibility; the engine is not thread-safe. See
+/// [Threads](crate#threads).
+unsafe impl Send for Account<'_> {}
 
-impl Account {
+impl<'book> Account<'book> {
     /// Creates a new Account in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::xaccMallocAccount(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("xaccMallocAccount returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -36,7 +41,7 @@ impl Account {
     /// # Safety
     /// The pointer must be valid and point to a properly initialized Account.
     pub unsafe fn from_raw(ptr: *mut ffi::Account, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer to the underlying Account.
@@ -170,77 +175,205 @@ impl Account {
         unsafe { ffi::gnc_account_is_root(self.ptr.as_ptr()) != 0 }
     }
 
+    /// Returns true if this account is tax-related.
+    pub fn is_tax_related(&self) -> bool {
+        unsafe { ffi::xaccAccountGetTaxRelated(self.ptr.as_ptr()) != 0 }
+    }
+
+    /// Returns the account's US tax code (e.g. "N261").
+    pub fn tax_us_code(&self) -> Option<String> {
+        unsafe {
+            let ptr = ffi::xaccAccountGetTaxUSCode(self.ptr.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Returns where the US tax payer name comes from ("current" or "parent").
+    pub fn tax_us_payer_name_source(&self) -> Option<String> {
+        unsafe {
+            let ptr = ffi::xaccAccountGetTaxUSPayerNameSource(self.ptr.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+            }
+        }
+    }
+
     // ==================== Setters ====================
 
+    /// Checks that this account can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "account") }
+    }
+
     /// Sets the account's name.
     ///
-    /// # Panics
-    ///
-    /// Panics if `name` contains a null byte.
-    pub fn set_name(&self, name: &str) {
-        let c_name = CString::new(name).unwrap();
+    /// # Errors
+    /// Besides the usual setter errors, returns
+    /// [`Error::InvalidOperation`] for a name that
+    /// [`check_name`](Self::check_name) rejects.
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        self.check_editable()?;
+        Self::check_name(name)?;
+        let c_name = CString::new(name)?;
         unsafe { ffi::xaccAccountSetName(self.ptr.as_ptr(), c_name.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the account's code.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `code` contains a null byte.
-    pub fn set_code(&self, code: &str) {
-        let c_code = CString::new(code).unwrap();
+    pub fn set_code(&self, code: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_code = CString::new(code)?;
         unsafe { ffi::xaccAccountSetCode(self.ptr.as_ptr(), c_code.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the account's description.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `desc` contains a null byte.
-    pub fn set_description(&self, desc: &str) {
-        let c_desc = CString::new(desc).unwrap();
+    pub fn set_description(&self, desc: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_desc = CString::new(desc)?;
         unsafe { ffi::xaccAccountSetDescription(self.ptr.as_ptr(), c_desc.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the account's notes.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `notes` contains a null byte.
-    pub fn set_notes(&self, notes: &str) {
-        let c_notes = CString::new(notes).unwrap();
+    pub fn set_notes(&self, notes: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_notes = CString::new(notes)?;
         unsafe { ffi::xaccAccountSetNotes(self.ptr.as_ptr(), c_notes.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the account's color.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `color` contains a null byte.
-    pub fn set_color(&self, color: &str) {
-        let c_color = CString::new(color).unwrap();
+    pub fn set_color(&self, color: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_color = CString::new(color)?;
         unsafe { ffi::xaccAccountSetColor(self.ptr.as_ptr(), c_color.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the account type.
-    pub fn set_type(&self, account_type: GNCAccountType) {
+    pub fn set_type(&self, account_type: GNCAccountType) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccAccountSetType(self.ptr.as_ptr(), account_type) }
+        Ok(())
     }
 
     /// Sets the placeholder flag.
-    pub fn set_placeholder(&self, val: bool) {
+    pub fn set_placeholder(&self, val: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccAccountSetPlaceholder(self.ptr.as_ptr(), val as i32) }
+        Ok(())
     }
 
     /// Sets the hidden flag.
-    pub fn set_hidden(&self, val: bool) {
+    pub fn set_hidden(&self, val: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccAccountSetHidden(self.ptr.as_ptr(), val as i32) }
+        Ok(())
+    }
+
+    /// Sets the tax-related flag.
+    pub fn set_tax_related(&self, val: bool) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::xaccAccountSetTaxRelated(self.ptr.as_ptr(), val as i32) }
+        Ok(())
+    }
+
+    /// Sets the account's US tax code.
+    pub fn set_tax_us_code(&self, code: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_code = CString::new(code)?;
+        unsafe { ffi::xaccAccountSetTaxUSCode(self.ptr.as_ptr(), c_code.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets where the US tax payer name comes from ("current" or "parent").
+    pub fn set_tax_us_payer_name_source(&self, source: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_source = CString::new(source)?;
+        unsafe { ffi::xaccAccountSetTaxUSPayerNameSource(self.ptr.as_ptr(), c_source.as_ptr()) }
+        Ok(())
+    }
+
+    // ==================== Commodity ====================
+
+    /// Returns the commodity (currency or security) this account is denominated in.
+    pub fn commodity(&self) -> Option<Commodity<'book>> {
+        unsafe { Commodity::from_raw(ffi::xaccAccountGetCommodity(self.ptr.as_ptr()), false) }
+    }
+
+    /// Sets the commodity this account is denominated in.
+    pub fn set_commodity(&self, commodity: &Commodity) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::xaccAccountSetCommodity(self.ptr.as_ptr(), commodity.as_ptr()) }
+        Ok(())
+    }
+
+    /// Returns this account's commodity if it is a currency, otherwise the
+    /// currency of the nearest ancestor that has one.
+    pub fn currency_or_parent(&self) -> Option<Commodity<'book>> {
+        unsafe {
+            let ptr = ffi::gnc_account_get_currency_or_parent(self.ptr.as_ptr());
+            Commodity::from_raw(ptr, false)
+        }
+    }
+
+    /// Sets a non-standard smallest commodity unit for this account.
+    pub fn set_commodity_scu(&self, scu: i32) -> Result<()> {
+        self.check_editable()?;
+        unsafe {
+            ffi::xaccAccountSetCommoditySCU(self.ptr.as_ptr(), scu);
+            ffi::xaccAccountSetNonStdSCU(self.ptr.as_ptr(), 1);
+        }
+        Ok(())
     }
 
     // ==================== Hierarchy ====================
 
+    /// Returns the separator between account names in full names (`:` by
+    /// default; configurable in GnuCash's preferences).
+    pub fn separator() -> String {
+        unsafe {
+            let ptr = ffi::gnc_get_account_separator_string();
+            if ptr.is_null() {
+                ":".to_string()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        }
+    }
+
+    /// Checks that `name` can be used as an account name.
+    ///
+    /// A name must not be blank, and must not contain the account separator,
+    /// which would make the account's full name ambiguous.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidOperation`] describing the problem.
+    pub fn check_name(name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(Error::InvalidOperation(
+                "account names cannot be empty".to_string(),
+            ));
+        }
+        let separator = Self::separator();
+        if name.contains(separator.as_str()) {
+            return Err(Error::InvalidOperation(format!(
+                "account name {:?} contains the account separator {:?}",
+                name, separator
+            )));
+        }
+        Ok(())
+    }
+
     /// Returns the parent account, if any.
-    pub fn parent(&self) -> Option<Account> {
+    pub fn parent(&self) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::gnc_account_get_parent(self.ptr.as_ptr());
             Self::from_raw(ptr, false)
@@ -248,7 +381,7 @@ impl Account {
     }
 
     /// Returns the root account of the tree this account belongs to.
-    pub fn root(&self) -> Option<Account> {
+    pub fn root(&self) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::gnc_account_get_root(self.ptr.as_ptr());
             Self::from_raw(ptr, false)
@@ -271,7 +404,7 @@ impl Account {
     }
 
     /// Returns the n'th child account.
-    pub fn nth_child(&self, n: i32) -> Option<Account> {
+    pub fn nth_child(&self, n: i32) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::gnc_account_nth_child(self.ptr.as_ptr(), n);
             Self::from_raw(ptr, false)
@@ -301,7 +434,7 @@ impl Account {
     }
 
     /// Looks up an account by name among descendants.
-    pub fn lookup_by_name(&self, name: &str) -> Option<Account> {
+    pub fn lookup_by_name(&self, name: &str) -> Option<Account<'book>> {
         let c_name = CString::new(name).ok()?;
         unsafe {
             let ptr = ffi::gnc_account_lookup_by_name(self.ptr.as_ptr(), c_name.as_ptr());
@@ -310,7 +443,7 @@ impl Account {
     }
 
     /// Looks up an account by full name (e.g., "Assets:Bank:Checking").
-    pub fn lookup_by_full_name(&self, name: &str) -> Option<Account> {
+    pub fn lookup_by_full_name(&self, name: &str) -> Option<Account<'book>> {
         let c_name = CString::new(name).ok()?;
         unsafe {
             let ptr = ffi::gnc_account_lookup_by_full_name(self.ptr.as_ptr(), c_name.as_ptr());
@@ -319,7 +452,7 @@ impl Account {
     }
 
     /// Looks up an account by code among descendants.
-    pub fn lookup_by_code(&self, code: &str) -> Option<Account> {
+    pub fn lookup_by_code(&self, code: &str) -> Option<Account<'book>> {
         let c_code = CString::new(code).ok()?;
         unsafe {
             let ptr = ffi::gnc_account_lookup_by_code(self.ptr.as_ptr(), c_code.as_ptr());
@@ -327,6 +460,233 @@ impl Account {
         }
     }
 
+    // ==================== Restructuring ====================
+
+    /// Returns the objects that refer to this account, such as invoices,
+    /// bill entries, tax tables, employees and scheduled transactions.
+    ///
+    /// Splits are not included; see [`splits`](Self::splits).
+    pub fn referrers(&self) -> Vec<Referrer> {
+        unsafe { referring_objects(self.ptr.as_ptr().cast()) }
+    }
+
+    /// Moves this account, with its subaccounts, under `new_parent`.
+    ///
+    /// # Errors
+    /// * [`Error::ReadOnly`] if the book is read-only.
+    /// * [`Error::InvalidOperation`] if this account is not in an account
+    ///   tree or is its root, if `new_parent` is this account, one of its
+    ///   subaccounts or in another book, if `new_parent` already has a child
+    ///   of the same name (see [`merge_into`](Self::merge_into)), or if the
+    ///   account types cannot be nested.
+    pub fn move_to(&self, new_parent: &Account) -> Result<()> {
+        self.check_restructure(new_parent)?;
+        if self.parent().is_none() {
+            return Err(Error::InvalidOperation(format!(
+                "account {:?} is not in an account tree",
+                self.name().unwrap_or_default()
+            )));
+        }
+        if new_parent.has_ancestor(self) {
+            return Err(Error::InvalidOperation(
+                "cannot move an account under one of its subaccounts".to_string(),
+            ));
+        }
+        if self.parent().as_ref() == Some(new_parent) {
+            return Ok(());
+        }
+        if let Some(name) = self.name()
+            && new_parent.children().any(|c| c.name().as_deref() == Some(name.as_str()))
+        {
+            return Err(Error::InvalidOperation(format!(
+                "{:?} already has a subaccount named {:?}",
+                new_parent.full_name().unwrap_or_default(),
+                name
+            )));
+        }
+        check_nestable(new_parent, self)?;
+        unsafe { ffi::gnc_account_append_child(new_parent.ptr.as_ptr(), self.ptr.as_ptr()) }
+        Ok(())
+    }
+
+    /// Merges this account into `target` and destroys it.
+    ///
+    /// The account's splits move to `target`. Its subaccounts become
+    /// subaccounts of `target`, except that one with the same name as a
+    /// subaccount of `target` is merged into that one in turn.
+    ///
+    /// Nothing is changed unless every account to be destroyed has no
+    /// [`referrers`](Self::referrers) and every subaccount moved under
+    /// `target` can be nested there.
+    ///
+    /// # Errors
+    /// * [`Error::ReadOnly`] if the book is read-only.
+    /// * [`Error::InUse`] if an account to be destroyed is still referred
+    ///   to.
+    /// * [`Error::InvalidOperation`] if this account is the root, if
+    ///   `target` is this account, one of its subaccounts or in another
+    ///   book, if two merged accounts differ in type or, when the one
+    ///   merged away has splits, in commodity, or if a moved subaccount's
+    ///   type cannot be nested under its new parent.
+    ///
+    /// # Safety
+    /// This account and every subaccount merged into a same-named subaccount
+    /// of `target` are freed. No other [`Account`] handle to any of them may
+    /// be used afterwards, including handles obtained earlier from
+    /// [`children`](Self::children) or [`lookup_by_full_name`](Self::lookup_by_full_name).
+    pub unsafe fn merge_into(self, target: &Account) -> Result<()> {
+        self.check_restructure(target)?;
+        if target.has_ancestor(&self) {
+            return Err(Error::InvalidOperation(
+                "cannot merge an account into one of its subaccounts".to_string(),
+            ));
+        }
+        check_mergeable(&self, target)?;
+
+        let mut referrers = self.referrers();
+        self.check_merge_children(target, &mut referrers)?;
+        if !referrers.is_empty() {
+            return Err(in_use("account", referrers));
+        }
+
+        self.merge_children_into(target);
+        unsafe { ffi::xaccAccountMoveAllSplits(self.ptr.as_ptr(), target.ptr.as_ptr()) }
+        self.destroy();
+        Ok(())
+    }
+
+    /// Checks the subaccounts [`merge_into`](Self::merge_into) would merge,
+    /// collecting their referrers.
+    fn check_merge_children(&self, target: &Account, referrers: &mut Vec<Referrer>) -> Result<()> {
+        for child in self.children() {
+            let Some(twin) = child.name().and_then(|name| target.child_named(&name)) else {
+                check_nestable(target, &child)?;
+                continue;
+            };
+            check_mergeable(&child, &twin)?;
+            referrers.extend(child.referrers());
+            child.check_merge_children(&twin, referrers)?;
+        }
+        Ok(())
+    }
+
+    /// Merges same-named subaccounts into those of `target` and moves the
+    /// rest under it.
+    fn merge_children_into(&self, target: &Account) {
+        let children: Vec<_> = self.children().collect();
+        for child in children {
+            if let Some(twin) = child.name().and_then(|name| target.child_named(&name)) {
+                child.merge_children_into(&twin);
+                unsafe { ffi::xaccAccountMoveAllSplits(child.ptr.as_ptr(), twin.ptr.as_ptr()) }
+                child.destroy();
+            }
+        }
+        unsafe { ffi::gnc_account_join_children(target.ptr.as_ptr(), self.ptr.as_ptr()) }
+    }
+
+    /// Deletes this account.
+    ///
+    /// An account with splits must hand them to another account,
+    /// `reassign_splits_to`, in the same commodity. Subaccounts must be
+    /// moved or deleted first.
+    ///
+    /// # Errors
+    /// * [`Error::ReadOnly`] if the book is read-only.
+    /// * [`Error::InUse`] if other objects still refer to the account.
+    /// * [`Error::InvalidOperation`] if the account is the root or has
+    ///   subaccounts, if it has splits and `reassign_splits_to` is `None`,
+    ///   this account, in another book or in another commodity.
+    ///
+    /// # Safety
+    /// On success the account is freed. No other [`Account`] handle to it
+    /// may be used afterwards, including handles obtained earlier from
+    /// [`children`](Self::children) or [`lookup_by_full_name`](Self::lookup_by_full_name).
+    pub unsafe fn delete(self, reassign_splits_to: Option<&Account>) -> Result<()> {
+        unsafe { edit::check_book_writable(self.ptr.as_ptr().cast())? };
+        if self.is_root() {
+            return Err(Error::InvalidOperation("cannot delete the root account".to_string()));
+        }
+        if self.n_children() > 0 {
+            return Err(Error::InvalidOperation(format!(
+                "account {:?} has subaccounts; move or delete them first",
+                self.full_name().unwrap_or_default()
+            )));
+        }
+        let splits = self.splits_size();
+        match reassign_splits_to {
+            Some(target) if splits > 0 => {
+                self.check_restructure(target)?;
+                if self.commodity() != target.commodity() {
+                    return Err(Error::InvalidOperation(format!(
+                        "cannot move the splits of {:?} to {:?}: the accounts have different \
+                         commodities",
+                        self.full_name().unwrap_or_default(),
+                        target.full_name().unwrap_or_default()
+                    )));
+                }
+            }
+            None if splits > 0 => {
+                return Err(Error::InvalidOperation(format!(
+                    "account {:?} has {} splits; give an account to move them to",
+                    self.full_name().unwrap_or_default(),
+                    splits
+                )));
+            }
+            _ => {}
+        }
+
+        let referrers = self.referrers();
+        if !referrers.is_empty() {
+            return Err(in_use("account", referrers));
+        }
+
+        if let Some(target) = reassign_splits_to.filter(|_| splits > 0) {
+            unsafe { ffi::xaccAccountMoveAllSplits(self.ptr.as_ptr(), target.ptr.as_ptr()) }
+        }
+        self.destroy();
+        Ok(())
+    }
+
+    /// Checks that this account can be moved or merged to `other`.
+    fn check_restructure(&self, other: &Account) -> Result<()> {
+        unsafe { edit::check_book_writable(self.ptr.as_ptr().cast())? };
+        if self.is_root() {
+            return Err(Error::InvalidOperation("cannot move the root account".to_string()));
+        }
+        if self == other {
+            return Err(Error::InvalidOperation(
+                "an account cannot be moved or merged to itself".to_string(),
+            ));
+        }
+        let (book, other_book) = unsafe {
+            (
+                ffi::gnc_account_get_book(self.ptr.as_ptr()),
+                ffi::gnc_account_get_book(other.ptr.as_ptr()),
+            )
+        };
+        if book != other_book {
+            return Err(Error::InvalidOperation("the accounts are in different books".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns the immediate child named `name`.
+    fn child_named(&self, name: &str) -> Option<Account<'book>> {
+        self.children().find(|c| c.name().as_deref() == Some(name))
+    }
+
+    /// Destroys the account, which removes it from its parent.
+    ///
+    /// Other handles to the account dangle afterwards; the callers' `# Safety`
+    /// sections pass that on.
+    fn destroy(self) {
+        unsafe {
+            ffi::xaccAccountBeginEdit(self.ptr.as_ptr());
+            ffi::xaccAccountDestroy(self.ptr.as_ptr());
+        }
+        std::mem::forget(self);
+    }
+
     // ==================== Balances ====================
 
     /// Returns the current balance of the account.
@@ -355,8 +715,75 @@ impl Account {
     }
 
     /// Returns the balance as of a specific date.
-    pub fn balance_as_of_date(&self, date: i64) -> Numeric {
-        unsafe { ffi::xaccAccountGetBalanceAsOfDate(self.ptr.as_ptr(), date).into() }
+    pub fn balance_as_of_date(&self, date: Time64) -> Numeric {
+        unsafe { ffi::xaccAccountGetBalanceAsOfDate(self.ptr.as_ptr(), date.secs()).into() }
+    }
+
+    /// Returns the change in balance between `start` and `end`: the
+    /// balance as of `end` less the balance as of `start`.
+    ///
+    /// With `include_children`, subaccount balances are included, converted
+    /// to this account's commodity.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let start = Time64::from_dmy_end(31, 12, 2023)?;
+    /// let end = Time64::from_dmy_end(31, 12, 2024)?;
+    /// let spent_2024 = expenses.balance_change_for_period(start, end, true);
+    /// ```
+    pub fn balance_change_for_period(
+        &self,
+        start: Time64,
+        end: Time64,
+        include_children: bool,
+    ) -> Numeric {
+        unsafe {
+            ffi::xaccAccountGetBalanceChangeForPeriod(
+                self.ptr.as_ptr(),
+                start.secs(),
+                end.secs(),
+                include_children as i32,
+            )
+            .into()
+        }
+    }
+
+    /// Returns the current balance converted to `commodity`.
+    ///
+    /// With `include_children`, subaccount balances are included.
+    /// Conversions use the latest prices in the book's price database; a
+    /// balance with no price to `commodity` counts as zero.
+    pub fn balance_in_currency(&self, commodity: &Commodity, include_children: bool) -> Numeric {
+        unsafe {
+            ffi::xaccAccountGetBalanceInCurrency(
+                self.ptr.as_ptr(),
+                commodity.as_ptr(),
+                include_children as i32,
+            )
+            .into()
+        }
+    }
+
+    /// Returns the balance as of `date` converted to `commodity`.
+    ///
+    /// With `include_children`, subaccount balances are included.
+    /// Conversions use the book's prices as of `date`; a balance with no
+    /// price to `commodity` counts as zero.
+    pub fn balance_as_of_date_in_currency(
+        &self,
+        date: Time64,
+        commodity: &Commodity,
+        include_children: bool,
+    ) -> Numeric {
+        unsafe {
+            ffi::xaccAccountGetBalanceAsOfDateInCurrency(
+                self.ptr.as_ptr(),
+                date.secs(),
+                commodity.as_ptr(),
+                include_children as i32,
+            )
+            .into()
+        }
     }
 
     /// Recomputes the account balance.
@@ -379,22 +806,34 @@ impl Account {
     // ==================== Iterators ====================
 
     /// Returns an iterator over the immediate children of this account.
-    pub fn children(&self) -> AccountChildren {
+    pub fn children(&self) -> AccountChildren<'book> {
         AccountChildren::new(self)
     }
 
     /// Returns an iterator over all descendants of this account (depth-first).
-    pub fn descendants(&self) -> AccountDescendants {
+    pub fn descendants(&self) -> AccountDescendants<'book> {
         AccountDescendants::new(self)
     }
 
     /// Returns an iterator over the splits in this account.
-    pub fn splits(&self) -> AccountSplits {
+    pub fn splits(&self) -> AccountSplits<'book> {
         AccountSplits::new(self)
     }
 }
 
-impl Drop for Account {
+/// The engine cannot roll back accounts, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Account<'_> {
+    fn begin_edit(&self) {
+        Account::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Account::commit_edit(self)
+    }
+}
+
+impl Drop for Account<'_> {
     fn drop(&mut self) {
         if self.owned {
             unsafe {
@@ -405,7 +844,7 @@ impl Drop for Account {
     }
 }
 
-impl std::fmt::Debug for Account {
+impl std::fmt::Debug for Account<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Account")
             .field("guid", &self.guid())
@@ -416,20 +855,134 @@ impl std::fmt::Debug for Account {
     }
 }
 
-impl PartialEq for Account {
+impl PartialEq for Account<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.guid() == other.guid()
     }
 }
 
-impl Eq for Account {}
+impl Eq for Account<'_> {}
 
-impl std::hash::Hash for Account {
+impl std::hash::Hash for Account<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.guid().hash(state);
     }
 }
 
+/// An engine object that refers to an account or commodity (see
+/// [`Account::referrers`] and [`Commodity::referrers`](crate::Commodity::referrers)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Referrer {
+    /// The QOF type of the object, e.g. `"gncInvoice"` or `"gncTaxTable"`.
+    pub entity_type: String,
+    /// The GUID of the object.
+    pub guid: Guid,
+    /// The engine's display name for the object.
+    pub display_name: String,
+}
+
+impl Referrer {
+    /// # Safety
+    /// `inst` must point to a valid `QofInstance`.
+    pub(crate) unsafe fn from_instance(inst: *const ffi::QofInstance) -> Self {
+        unsafe {
+            let e_type = (*inst).e_type;
+            let guid = ffi::qof_instance_get_guid(inst.cast());
+            let name = ffi::qof_instance_get_display_name(inst);
+            let display_name = if name.is_null() {
+                String::new()
+            } else {
+                let s = CStr::from_ptr(name).to_string_lossy().into_owned();
+                ffi::g_free(name.cast());
+                s
+            };
+            Self {
+                entity_type: if e_type.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(e_type).to_string_lossy().into_owned()
+                },
+                guid: if guid.is_null() {
+                    *Guid::null()
+                } else {
+                    Guid::from(*guid)
+                },
+                display_name,
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Referrer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.display_name.is_empty() {
+            write!(f, "{} {}", self.entity_type, self.guid)
+        } else {
+            f.write_str(&self.display_name)
+        }
+    }
+}
+
+/// Returns the engine objects that refer to `inst`.
+///
+/// # Safety
+/// `inst` must point to a valid `QofInstance`.
+pub(crate) unsafe fn referring_objects(inst: *const ffi::QofInstance) -> Vec<Referrer> {
+    let mut referrers = Vec::new();
+    unsafe {
+        let list = ffi::qof_instance_get_referring_object_list(inst);
+        let mut node = list;
+        while !node.is_null() {
+            let inst = (*node).data as *const ffi::QofInstance;
+            if !inst.is_null() {
+                referrers.push(Referrer::from_instance(inst));
+            }
+            node = (*node).next;
+        }
+        ffi::g_list_free(list);
+    }
+    referrers
+}
+
+pub(crate) fn in_use(entity: &'static str, referrers: Vec<Referrer>) -> Error {
+    Error::InUse {
+        entity,
+        referrers: referrers.iter().map(Referrer::to_string).collect(),
+    }
+}
+
+/// Checks that `account` can be merged into `target`: same type, and same
+/// commodity if `account` has splits to move.
+fn check_mergeable(account: &Account, target: &Account) -> Result<()> {
+    let reason = if account.account_type() != target.account_type() {
+        "the accounts have different types"
+    } else if account.splits_size() > 0 && account.commodity() != target.commodity() {
+        "the accounts have different commodities"
+    } else {
+        return Ok(());
+    };
+    Err(Error::InvalidOperation(format!(
+        "cannot merge {:?} into {:?}: {}",
+        account.full_name().unwrap_or_default(),
+        target.full_name().unwrap_or_default(),
+        reason
+    )))
+}
+
+/// Checks that an account of `child`'s type can be placed under `parent`.
+fn check_nestable(parent: &Account, child: &Account) -> Result<()> {
+    let (parent_type, child_type) = (parent.account_type(), child.account_type());
+    if parent_type != GNCAccountType::ACCT_TYPE_ROOT
+        && unsafe { ffi::xaccAccountTypesCompatible(parent_type, child_type) } == 0
+    {
+        return Err(Error::InvalidOperation(format!(
+            "cannot place a {:?} account under a {:?} account",
+            child_type, parent_type
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -440,4 +993,236 @@ mod tests {
         assert_eq!(GNCAccountType::ACCT_TYPE_CASH as i32, 1);
         assert_eq!(GNCAccountType::ACCT_TYPE_ASSET as i32, 2);
     }
+
+    #[test]
+    fn test_account_currency_or_parent() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let eur = table.lookup_currency("EUR").unwrap();
+
+        let parent = Account::new(&book);
+        parent.begin_edit();
+        parent.set_commodity(&eur).unwrap();
+        parent.commit_edit();
+        let child = Account::new(&book);
+        parent.append_child(&child);
+
+        assert_eq!(parent.commodity(), Some(eur));
+        assert!(child.commodity().is_none());
+        assert_eq!(
+            child.currency_or_parent().and_then(|c| c.mnemonic()).as_deref(),
+            Some("EUR")
+        );
+    }
+
+    fn usd_account<'a>(book: &'a Book, path: &str, account_type: GNCAccountType) -> Account<'a> {
+        let usd = book.commodity_table().unwrap().lookup_currency("USD").unwrap();
+        let account = book.ensure_account(path, account_type).unwrap();
+        account.edit_with(|a| a.set_commodity(&usd)).unwrap();
+        account
+    }
+
+    /// Records a transfer of `cents` from `from` to `to`.
+    fn transfer<'a>(
+        book: &'a Book,
+        from: &Account,
+        to: &Account,
+        cents: i64,
+    ) -> crate::Transaction<'a> {
+        let usd = book.commodity_table().unwrap().lookup_currency("USD").unwrap();
+        let mut txn = crate::Transaction::new(book);
+        txn.edit_with(|txn| {
+            txn.set_currency(&usd)?;
+            for (account, value) in [(from, -cents), (to, cents)] {
+                let mut split = crate::Split::new(book);
+                split.set_account(account)?;
+                split.set_transaction(txn)?;
+                split.set_amount(Numeric::new(value, 100))?;
+                split.set_value(Numeric::new(value, 100))?;
+                split.mark_unowned();
+            }
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+        // Leave the transaction and its splits in the book
+        txn.mark_unowned();
+        txn
+    }
+
+    #[test]
+    fn test_period_and_currency_balances() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let usd = table.lookup_currency("USD").unwrap();
+        let eur = table.lookup_currency("EUR").unwrap();
+        let checking = usd_account(&book, "Assets:Checking", GNCAccountType::ACCT_TYPE_BANK);
+        let food = usd_account(&book, "Expenses:Food", GNCAccountType::ACCT_TYPE_EXPENSE);
+        let dining = usd_account(&book, "Expenses:Food:Dining", GNCAccountType::ACCT_TYPE_EXPENSE);
+
+        let post = |from: &Account, to: &Account, cents: i64, day: u32| {
+            // Undated, the new split sorts before the dated ones in `to`
+            transfer(&book, from, to, cents)
+                .edit_with(|t| t.set_date(crate::GncDate::from_dmy(day, 3, 2024)?))
+                .unwrap();
+        };
+        post(&checking, &food, 1000, 1);
+        post(&checking, &dining, 2500, 10);
+        post(&checking, &food, 400, 20);
+
+        let end_of = |day| Time64::from_dmy_end(day, 3, 2024).unwrap();
+        let change = food.balance_change_for_period(end_of(5), end_of(31), false);
+        assert!(change.equal(Numeric::new(400, 100)));
+        let change = food.balance_change_for_period(end_of(5), end_of(31), true);
+        assert!(change.equal(Numeric::new(2900, 100)));
+        let early = food.balance_as_of_date_in_currency(end_of(5), &usd, true);
+        assert!(early.equal(Numeric::new(1000, 100)));
+        assert!(food.balance_in_currency(&usd, true).equal(Numeric::new(3900, 100)));
+        assert!(food.balance_in_currency(&usd, false).equal(food.balance()));
+
+        // 1 EUR = 1.25 USD
+        let db = crate::PriceDB::get(&book).unwrap();
+        let price = crate::Price::new(&book);
+        price
+            .edit_with(|p| {
+                p.set_commodity(&eur)?;
+                p.set_currency(&usd)?;
+                p.set_time(end_of(1))?;
+                p.set_value(Numeric::new(125, 100))
+            })
+            .unwrap();
+        db.add_price(&price);
+        assert!(food.balance_in_currency(&eur, true).equal(Numeric::new(3120, 100)));
+    }
+
+    #[test]
+    fn test_move_to() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let cash = usd_account(&book, "Assets:Old:Cash", GNCAccountType::ACCT_TYPE_CASH);
+        let bank = usd_account(&book, "Assets:Bank", GNCAccountType::ACCT_TYPE_ASSET);
+        let old = cash.parent().unwrap();
+
+        cash.move_to(&bank).unwrap();
+        assert_eq!(cash.full_name().as_deref(), Some("Assets:Bank:Cash"));
+        assert_eq!(old.n_children(), 0);
+
+        assert!(bank.move_to(&cash).is_err());
+        usd_account(&book, "Assets:Old:Cash", GNCAccountType::ACCT_TYPE_CASH);
+        assert!(old.children().next().unwrap().move_to(&bank).is_err());
+        let income = usd_account(&book, "Income", GNCAccountType::ACCT_TYPE_INCOME);
+        assert!(cash.move_to(&income).is_err());
+    }
+
+    #[test]
+    fn test_merge_into() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let checking = usd_account(&book, "Assets:Checking", GNCAccountType::ACCT_TYPE_BANK);
+        let food = usd_account(&book, "Expenses:Food", GNCAccountType::ACCT_TYPE_EXPENSE);
+        let dining = usd_account(&book, "Expenses:Food:Dining", GNCAccountType::ACCT_TYPE_EXPENSE);
+        let meals = usd_account(&book, "Expenses:Meals", GNCAccountType::ACCT_TYPE_EXPENSE);
+        let meals_dining =
+            usd_account(&book, "Expenses:Meals:Dining", GNCAccountType::ACCT_TYPE_EXPENSE);
+        let lunch = usd_account(&book, "Expenses:Meals:Lunch", GNCAccountType::ACCT_TYPE_EXPENSE);
+        transfer(&book, &checking, &meals, 1000);
+        transfer(&book, &checking, &meals_dining, 2500);
+
+        // `meals` and `meals_dining` are freed; neither is used again
+        unsafe { meals.merge_into(&food) }.unwrap();
+        let root = book.root_account().unwrap();
+        assert!(root.lookup_by_full_name("Expenses:Meals").is_none());
+        assert_eq!(food.splits_size(), 1);
+        assert_eq!(dining.splits_size(), 1);
+        assert_eq!(lunch.full_name().as_deref(), Some("Expenses:Food:Lunch"));
+        assert_eq!(food.n_children(), 2);
+
+        assert!(matches!(
+            unsafe { checking.parent().unwrap().merge_into(&checking) },
+            Err(Error::InvalidOperation(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_into_refuses_other_type() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let checking = usd_account(&book, "Assets:Checking", GNCAccountType::ACCT_TYPE_BANK);
+        let food = usd_account(&book, "Expenses:Food", GNCAccountType::ACCT_TYPE_EXPENSE);
+        transfer(&book, &checking, &food, 1000);
+
+        let root = book.root_account().unwrap();
+        let lookup = |name: &str| root.lookup_by_full_name(name).unwrap();
+        assert!(matches!(
+            unsafe { lookup("Assets:Checking").merge_into(&food) },
+            Err(Error::InvalidOperation(_))
+        ));
+        assert_eq!(checking.splits_size(), 1);
+        assert_eq!(food.splits_size(), 1);
+    }
+
+    #[test]
+    fn test_merge_into_refuses_unnestable_child() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let food = usd_account(&book, "Expenses:Food", GNCAccountType::ACCT_TYPE_EXPENSE);
+        let misc = usd_account(&book, "Expenses:Misc", GNCAccountType::ACCT_TYPE_EXPENSE);
+        let card = usd_account(&book, "Liabilities:Card", GNCAccountType::ACCT_TYPE_CREDIT);
+        // append_child does not check types, so build the bad tree directly
+        misc.append_child(&card);
+
+        let root = book.root_account().unwrap();
+        assert!(matches!(
+            unsafe { root.lookup_by_full_name("Expenses:Misc").unwrap().merge_into(&food) },
+            Err(Error::InvalidOperation(_))
+        ));
+        assert_eq!(card.full_name().as_deref(), Some("Expenses:Misc:Card"));
+        assert_eq!(food.n_children(), 0);
+    }
+
+    #[test]
+    fn test_delete() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let checking = usd_account(&book, "Assets:Checking", GNCAccountType::ACCT_TYPE_BANK);
+        let savings = usd_account(&book, "Assets:Savings", GNCAccountType::ACCT_TYPE_BANK);
+        let fees = usd_account(&book, "Expenses:Fees", GNCAccountType::ACCT_TYPE_EXPENSE);
+        transfer(&book, &checking, &fees, 500);
+
+        let root = book.root_account().unwrap();
+        let lookup = |name: &str| root.lookup_by_full_name(name).unwrap();
+        assert!(unsafe { lookup("Assets").delete(None) }.is_err());
+        assert!(unsafe { lookup("Assets:Checking").delete(None) }.is_err());
+        // `checking` is freed; it is not used again
+        unsafe { lookup("Assets:Checking").delete(Some(&savings)) }.unwrap();
+        assert!(root.lookup_by_full_name("Assets:Checking").is_none());
+        assert_eq!(savings.splits_size(), 1);
+    }
+
+    #[test]
+    fn test_delete_refuses_referred_account() {
+        let book = Book::new();
+        book.commodity_table().unwrap().add_default_data();
+        let sales_tax =
+            usd_account(&book, "Liabilities:Sales Tax", GNCAccountType::ACCT_TYPE_LIABILITY);
+        unsafe {
+            let table = ffi::gncTaxTableCreate(book.as_ptr());
+            ffi::gncTaxTableBeginEdit(table);
+            let entry = ffi::gncTaxTableEntryCreate();
+            ffi::gncTaxTableEntrySetAccount(entry, sales_tax.as_ptr());
+            ffi::gncTaxTableAddEntry(table, entry);
+            ffi::gncTaxTableCommitEdit(table);
+        }
+
+        let referrers = sales_tax.referrers();
+        assert_eq!(referrers.len(), 1);
+        assert_eq!(referrers[0].entity_type, "gncTaxTable");
+        assert!(matches!(
+            unsafe { sales_tax.delete(None) },
+            Err(Error::InUse { entity: "account", .. })
+        ));
+        let root = book.root_account().unwrap();
+        assert!(root.lookup_by_full_name("Liabilities:Sales Tax").is_some());
+    }
 }
@@ -1,9 +1,12 @@
 //! Safe wrapper for QofBook.
 
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::ops::Deref;
 use std::ptr::NonNull;
 
 use crate::ffi;
-use crate::{Account, Guid};
+use crate::{Account, CommodityTable, Editable, Error, GNCAccountType, Guid, Result};
 
 /// A GnuCash Book - the top-level container for all financial data.
 ///
@@ -16,6 +19,8 @@ pub struct Book {
 
 // Book is not thread-safe (GnuCash uses GLib which is not thread-safe)
 // but it can be sent between threads if properly synchronized
+/// Kept for compatibility; the engine is not thread-safe. See
+/// [Threads](crate#threads).
 unsafe impl Send for Book {}
 
 impl Book {
@@ -122,13 +127,170 @@ impl Book {
     }
 
     /// Returns the root account for this book.
-    pub fn root_account(&self) -> Option<Account> {
+    pub fn root_account(&self) -> Option<Account<'_>> {
         unsafe { Account::from_raw(self.root_account_ptr(), false) }
     }
 
     /// Sets the root account for this book.
-    pub fn set_root_account(&self, root: &Account) {
+    ///
+    /// Returns [`Error::ReadOnly`] if the book is read-only.
+    pub fn set_root_account(&self, root: &Account) -> Result<()> {
+        if self.is_readonly() {
+            return Err(Error::ReadOnly);
+        }
         unsafe { ffi::gnc_book_set_root_account(self.ptr.as_ptr(), root.as_ptr()) }
+        Ok(())
+    }
+
+    /// Returns the account at `path`, creating it and any missing parents.
+    ///
+    /// `path` is a full account name using the book's account separator
+    /// (see [`Account::separator`]), e.g. `"Expenses:Food:Groceries"`.
+    /// Existing accounts are found with `lookup_by_full_name` and reused.
+    ///
+    /// A missing last account is created with `account_type`. Missing
+    /// parents get the top-level type of its category, e.g. `ASSET` above a
+    /// `BANK` account and `EXPENSE` above an `EXPENSE` one. New accounts take
+    /// their parent's commodity, if it has one. The account tree owns the
+    /// accounts it creates, so the returned handle needs no `mark_unowned`.
+    ///
+    /// # Errors
+    /// * [`Error::ReadOnly`] if the book is read-only.
+    /// * [`Error::InvalidOperation`] if `path` has an empty name, if the
+    ///   last account exists with a different type, or if a new account's
+    ///   type cannot be placed under its parent's.
+    /// * [`Error::InvalidAccountType`] for `ACCT_TYPE_ROOT`, `ACCT_TYPE_NONE`
+    ///   and the legacy types past `NUM_ACCOUNT_TYPES`.
+    pub fn ensure_account(&self, path: &str, account_type: GNCAccountType) -> Result<Account<'_>> {
+        if self.is_readonly() {
+            return Err(Error::ReadOnly);
+        }
+        let raw_type = account_type as i32;
+        if account_type == GNCAccountType::ACCT_TYPE_ROOT
+            || !(0..GNCAccountType::NUM_ACCOUNT_TYPES as i32).contains(&raw_type)
+        {
+            return Err(Error::InvalidAccountType(account_type as i32));
+        }
+
+        let separator = Account::separator();
+        let names: Vec<&str> = path.split(separator.as_str()).collect();
+        for name in &names {
+            Account::check_name(name).map_err(|e| {
+                Error::InvalidOperation(format!("invalid account path {:?}: {}", path, e))
+            })?;
+        }
+
+        let root = self.root_account().ok_or(Error::NullPointer("root account"))?;
+        let mut parent: Option<Account<'_>> = None;
+        for (depth, name) in names.iter().enumerate() {
+            let is_leaf = depth + 1 == names.len();
+            let wanted = if is_leaf {
+                account_type
+            } else {
+                top_level_type(account_type)
+            };
+            let full_name = names[..=depth].join(&separator);
+
+            let account = match root.lookup_by_full_name(&full_name) {
+                Some(existing) => {
+                    if is_leaf && existing.account_type() != account_type {
+                        return Err(Error::InvalidOperation(format!(
+                            "account {:?} exists with type {:?}, not {:?}",
+                            full_name,
+                            existing.account_type(),
+                            account_type
+                        )));
+                    }
+                    existing
+                }
+                None => {
+                    let parent = parent.as_ref().unwrap_or(&root);
+                    self.create_child_account(parent, name, wanted)?
+                }
+            };
+            parent = Some(account);
+        }
+        Ok(parent.expect("account path has at least one name"))
+    }
+
+    /// Creates an account named `name` under `parent` and hands it to the
+    /// account tree.
+    fn create_child_account(
+        &self,
+        parent: &Account<'_>,
+        name: &str,
+        account_type: GNCAccountType,
+    ) -> Result<Account<'_>> {
+        let parent_type = parent.account_type();
+        if parent_type != GNCAccountType::ACCT_TYPE_ROOT
+            && unsafe { ffi::xaccAccountTypesCompatible(parent_type, account_type) } == 0
+        {
+            return Err(Error::InvalidOperation(format!(
+                "cannot create {:?} account {:?} under a {:?} account",
+                account_type, name, parent_type
+            )));
+        }
+
+        let mut account = Account::new(self);
+        account.edit_with(|a| {
+            a.set_name(name)?;
+            a.set_type(account_type)?;
+            if let Some(commodity) = parent.commodity() {
+                a.set_commodity(&commodity)?;
+            }
+            Ok::<_, Error>(())
+        })?;
+        parent.append_child(&account);
+        account.mark_unowned();
+        Ok(account)
+    }
+
+    /// Returns the accounts whose names contain the account separator, or
+    /// `None` if there are none.
+    ///
+    /// Such names come from files written with another separator, or from
+    /// before the separator was changed. Their full names are ambiguous, so
+    /// `lookup_by_full_name` cannot find them; rename them with
+    /// [`Account::set_name`].
+    pub fn account_name_violations(&self) -> Option<NameViolations> {
+        let separator = Account::separator();
+        let c_separator = CString::new(separator.as_str()).ok()?;
+        unsafe {
+            let list = ffi::gnc_account_list_name_violations(self.as_ptr(), c_separator.as_ptr());
+            if list.is_null() {
+                return None;
+            }
+            let mut names = Vec::new();
+            let mut node = list;
+            while !node.is_null() {
+                let name = (*node).data as *const std::os::raw::c_char;
+                if !name.is_null() {
+                    names.push(CStr::from_ptr(name).to_string_lossy().into_owned());
+                }
+                node = (*node).next;
+            }
+            let msg = ffi::gnc_account_name_violations_errmsg(c_separator.as_ptr(), list);
+            let message = if msg.is_null() {
+                String::new()
+            } else {
+                let message = CStr::from_ptr(msg).to_string_lossy().into_owned();
+                ffi::g_free(msg.cast());
+                message
+            };
+            ffi::g_list_free_full(list, Some(ffi::g_free));
+            // The engine builds the list back to front
+            names.reverse();
+            Some(NameViolations {
+                separator,
+                names,
+                message,
+            })
+        }
+    }
+
+    /// Returns the commodity table for this book.
+    pub fn commodity_table(&self) -> Option<CommodityTable<'_>> {
+        CommodityTable::get_table(self)
     }
 
     /// Returns the number of transactions in this book.
@@ -137,6 +299,46 @@ impl Book {
     }
 }
 
+/// Returns the top-level type of an account type's category, used for
+/// parents created by [`Book::ensure_account`].
+fn top_level_type(account_type: GNCAccountType) -> GNCAccountType {
+    use GNCAccountType::*;
+
+    match account_type {
+        ACCT_TYPE_BANK | ACCT_TYPE_CASH | ACCT_TYPE_ASSET | ACCT_TYPE_STOCK | ACCT_TYPE_MUTUAL
+        | ACCT_TYPE_CURRENCY | ACCT_TYPE_RECEIVABLE => ACCT_TYPE_ASSET,
+        ACCT_TYPE_CREDIT | ACCT_TYPE_LIABILITY | ACCT_TYPE_PAYABLE => ACCT_TYPE_LIABILITY,
+        other => other,
+    }
+}
+
+/// Account names that contain the account separator, from
+/// [`Book::account_name_violations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameViolations {
+    /// The separator the names were checked against.
+    pub separator: String,
+    /// The offending account names (not full names).
+    pub names: Vec<String>,
+    /// The engine's explanation for the user, listing the names.
+    pub message: String,
+}
+
+impl std::fmt::Display for NameViolations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.message.is_empty() {
+            write!(
+                f,
+                "account names contain the separator {:?}: {}",
+                self.separator,
+                self.names.join(", ")
+            )
+        } else {
+            f.write_str(&self.message)
+        }
+    }
+}
+
 impl Default for Book {
     fn default() -> Self {
         Self::new()
@@ -178,6 +380,42 @@ impl std::hash::Hash for Book {
     }
 }
 
+/// A borrowed handle to the book an entity belongs to.
+///
+/// Dereferences to [`Book`] and cannot outlive the book it was obtained from.
+pub struct BookRef<'book> {
+    book: Book,
+    _book: PhantomData<&'book Book>,
+}
+
+impl BookRef<'_> {
+    /// Wraps a book pointer without taking ownership.
+    ///
+    /// # Safety
+    /// The pointer must be null or point to a valid QofBook that outlives the handle.
+    pub unsafe fn from_raw(ptr: *mut ffi::QofBook) -> Option<Self> {
+        let book = unsafe { Book::from_raw(ptr, false) }?;
+        Some(Self {
+            book,
+            _book: PhantomData,
+        })
+    }
+}
+
+impl Deref for BookRef<'_> {
+    type Target = Book;
+
+    fn deref(&self) -> &Book {
+        &self.book
+    }
+}
+
+impl std::fmt::Debug for BookRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.book.fmt(f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,4 +446,72 @@ mod tests {
         book.mark_readonly();
         assert!(book.is_readonly());
     }
+
+    #[test]
+    fn test_ensure_account_creates_and_reuses() {
+        let book = Book::new();
+        let groceries = book
+            .ensure_account("Expenses:Food:Groceries", GNCAccountType::ACCT_TYPE_EXPENSE)
+            .unwrap();
+        assert_eq!(groceries.full_name().as_deref(), Some("Expenses:Food:Groceries"));
+
+        let checking = book
+            .ensure_account("Assets:Bank:Checking", GNCAccountType::ACCT_TYPE_BANK)
+            .unwrap();
+        let bank = checking.parent().unwrap();
+        assert_eq!(bank.account_type(), GNCAccountType::ACCT_TYPE_ASSET);
+
+        let again = book
+            .ensure_account("Expenses:Food:Groceries", GNCAccountType::ACCT_TYPE_EXPENSE)
+            .unwrap();
+        assert_eq!(again.guid(), groceries.guid());
+        assert_eq!(book.root_account().unwrap().n_children(), 2);
+    }
+
+    #[test]
+    fn test_ensure_account_rejects_bad_input() {
+        let book = Book::new();
+        assert!(matches!(
+            book.ensure_account("Assets::Checking", GNCAccountType::ACCT_TYPE_BANK),
+            Err(Error::InvalidOperation(_))
+        ));
+        assert!(matches!(
+            book.ensure_account("Root", GNCAccountType::ACCT_TYPE_ROOT),
+            Err(Error::InvalidAccountType(_))
+        ));
+
+        book.ensure_account("Income:Salary", GNCAccountType::ACCT_TYPE_INCOME).unwrap();
+        assert!(matches!(
+            book.ensure_account("Income:Salary", GNCAccountType::ACCT_TYPE_EXPENSE),
+            Err(Error::InvalidOperation(_))
+        ));
+        assert!(matches!(
+            book.ensure_account("Income:Salary:Groceries", GNCAccountType::ACCT_TYPE_BANK),
+            Err(Error::InvalidOperation(_))
+        ));
+    }
+
+    #[test]
+    fn test_account_name_violations() {
+        let book = Book::new();
+        let checking = book
+            .ensure_account("Assets:Checking", GNCAccountType::ACCT_TYPE_BANK)
+            .unwrap();
+        assert!(book.account_name_violations().is_none());
+
+        // set_name refuses such names; files from other separators have them
+        assert!(checking.edit_with(|a| a.set_name("Old:Checking")).is_err());
+        let c_name = CString::new("Old:Checking").unwrap();
+        checking.begin_edit();
+        unsafe { ffi::xaccAccountSetName(checking.as_ptr(), c_name.as_ptr()) };
+        checking.commit_edit();
+
+        let violations = book.account_name_violations().unwrap();
+        assert_eq!(violations.separator, ":");
+        assert_eq!(violations.names, ["Old:Checking"]);
+        assert!(violations.to_string().contains("Old:Checking"));
+
+        checking.edit_with(|a| a.set_name("Old Checking")).unwrap();
+        assert!(book.account_name_violations().is_none());
+    }
 }
@@ -0,0 +1,726 @@
+//! Safe wrappers for GnuCash Commodity and CommodityTable.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::{edit, ffi};
+use crate::account::{in_use, referring_objects};
+use crate::{Book, Editable, Guid, PriceDB, Referrer, Result};
+
+/// Standard commodity namespace names.
+pub mod namespace {
+    /// ISO 4217 currencies.
+    pub const CURRENCY: &str = "CURRENCY";
+    /// Template commodities used by scheduled transactions.
+    pub const TEMPLATE: &str = "template";
+    /// NASDAQ listed securities.
+    pub const NASDAQ: &str = "NASDAQ";
+    /// NYSE listed securities.
+    pub const NYSE: &str = "NYSE";
+    /// EUREX listed securities.
+    pub const EUREX: &str = "EUREX";
+    /// Mutual funds.
+    pub const MUTUAL: &str = "FUND";
+    /// AMEX listed securities.
+    pub const AMEX: &str = "AMEX";
+}
+
+/// A GnuCash Commodity - a currency, stock, fund or other tradable unit.
+///
+/// Commodities created with [`Commodity::new`] are owned until they are
+/// inserted into a [`CommodityTable`]; commodities obtained from the
+/// table are borrowed from the book.
+pub struct Commodity<'book> {
+    ptr: NonNull<ffi::gnc_commodity>,
+    owned: bool,
+    _book: PhantomData<&'book Book>,
+}
+
+impl<'book> Commodity<'book> {
+    /// Creates a new Commodity in the given book.
+    ///
+    /// `fraction` is the smallest tradable unit expressed as a denominator,
+    /// e.g. 100 for cents.
+    ///
+    /// Returns [`Error::StringConversion`](crate::Error::StringConversion)
+    /// if any of the strings contain a null byte.
+    pub fn new(
+        book: &'book Book,
+        fullname: &str,
+        namespace: &str,
+        mnemonic: &str,
+        cusip: Option<&str>,
+        fraction: i32,
+    ) -> Result<Self> {
+        let c_fullname = CString::new(fullname)?;
+        let c_namespace = CString::new(namespace)?;
+        let c_mnemonic = CString::new(mnemonic)?;
+        let c_cusip = cusip.map(CString::new).transpose()?;
+        let ptr = unsafe {
+            ffi::gnc_commodity_new(
+                book.as_ptr(),
+                c_fullname.as_ptr(),
+                c_namespace.as_ptr(),
+                c_mnemonic.as_ptr(),
+                c_cusip.as_ref().map_or(std::ptr::null(), |c| c.as_ptr()),
+                fraction,
+            )
+        };
+        Ok(Self {
+            ptr: NonNull::new(ptr).expect("gnc_commodity_new returned null"),
+            owned: true,
+            _book: PhantomData,
+        })
+    }
+
+    /// Creates a Commodity wrapper from a raw pointer.
+    ///
+    /// # Safety
+    /// The pointer must be valid and point to a properly initialized gnc_commodity.
+    /// If `owned` is true, the commodity will be destroyed when this wrapper is dropped.
+    pub unsafe fn from_raw(ptr: *mut ffi::gnc_commodity, owned: bool) -> Option<Self> {
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
+    }
+
+    /// Returns the raw pointer to the underlying gnc_commodity.
+    pub fn as_ptr(&self) -> *mut ffi::gnc_commodity {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns the GUID of this commodity.
+    pub fn guid(&self) -> Guid {
+        unsafe {
+            let instance = self.ptr.as_ptr() as *const c_void;
+            let guid_ptr = ffi::qof_instance_get_guid(instance);
+            if guid_ptr.is_null() {
+                Guid::from_bytes([0; 16])
+            } else {
+                Guid::from_bytes((*guid_ptr).reserved)
+            }
+        }
+    }
+
+    /// Looks up a commodity by GUID.
+    pub fn lookup_by_guid(guid: &Guid, book: &'book Book) -> Option<Commodity<'book>> {
+        unsafe {
+            let ptr = ffi::gnc_commodity_find_commodity_by_guid(guid.as_ffi(), book.as_ptr());
+            Self::from_raw(ptr, false)
+        }
+    }
+
+    /// Begins an edit session on this commodity.
+    pub fn begin_edit(&self) {
+        unsafe { ffi::gnc_commodity_begin_edit(self.ptr.as_ptr()) }
+    }
+
+    /// Commits changes made during the edit session.
+    pub fn commit_edit(&self) {
+        unsafe { ffi::gnc_commodity_commit_edit(self.ptr.as_ptr()) }
+    }
+
+    /// Marks this commodity as not owned (ownership transferred to the table).
+    pub(crate) fn mark_unowned(&mut self) {
+        self.owned = false;
+    }
+
+    // ==================== Getters ====================
+
+    /// Returns the mnemonic (ticker symbol or ISO code), e.g. "USD" or "ACME".
+    pub fn mnemonic(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_mnemonic(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the namespace, e.g. "CURRENCY" or "NASDAQ".
+    pub fn namespace(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_namespace(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the full name, e.g. "US Dollar".
+    pub fn fullname(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_fullname(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the print name, e.g. "USD (US Dollar)".
+    pub fn printname(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_printname(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the unique name ("namespace::mnemonic").
+    pub fn unique_name(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_unique_name(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the CUSIP or other exchange code.
+    pub fn cusip(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_cusip(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the smallest tradable fraction (e.g. 100 for cents).
+    pub fn fraction(&self) -> i32 {
+        unsafe { ffi::gnc_commodity_get_fraction(self.ptr.as_ptr()) }
+    }
+
+    /// Returns true if online price quotes are enabled.
+    pub fn quote_flag(&self) -> bool {
+        unsafe { ffi::gnc_commodity_get_quote_flag(self.ptr.as_ptr()) != 0 }
+    }
+
+    /// Returns the online price quote source.
+    pub fn quote_source(&self) -> Option<QuoteSource> {
+        unsafe { QuoteSource::from_raw(ffi::gnc_commodity_get_quote_source(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the time zone used for price quotes.
+    pub fn quote_tz(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_quote_tz(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the display symbol, e.g. "$".
+    pub fn nice_symbol(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_commodity_get_nice_symbol(self.ptr.as_ptr())) }
+    }
+
+    /// Returns true if this commodity is a currency.
+    pub fn is_currency(&self) -> bool {
+        unsafe { ffi::gnc_commodity_is_currency(self.ptr.as_ptr()) != 0 }
+    }
+
+    /// Returns true if this commodity is an ISO 4217 currency.
+    pub fn is_iso(&self) -> bool {
+        unsafe { ffi::gnc_commodity_is_iso(self.ptr.as_ptr()) != 0 }
+    }
+
+    /// Returns the objects that use this commodity: accounts, transactions,
+    /// prices for or in it, invoices and the like.
+    pub fn referrers(&self) -> Vec<Referrer> {
+        unsafe {
+            let inst = self.ptr.as_ptr().cast::<ffi::QofInstance>();
+            let mut referrers = referring_objects(inst);
+            let db = ffi::gnc_pricedb_get_db(ffi::qof_instance_get_book(inst.cast()));
+            if let Some(db) = PriceDB::from_raw(db, false) {
+                for price in db.prices() {
+                    let uses = price.commodity().as_ref() == Some(self)
+                        || price.currency().as_ref() == Some(self);
+                    if uses && !referrers.iter().any(|r| r.guid == price.guid()) {
+                        referrers.push(Referrer::from_instance(price.as_ptr().cast()));
+                    }
+                }
+            }
+            referrers
+        }
+    }
+
+    // ==================== Setters ====================
+
+    /// Checks that this commodity can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "commodity") }
+    }
+
+    /// Sets the mnemonic.
+    pub fn set_mnemonic(&self, mnemonic: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_mnemonic = CString::new(mnemonic)?;
+        unsafe { ffi::gnc_commodity_set_mnemonic(self.ptr.as_ptr(), c_mnemonic.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the namespace.
+    pub fn set_namespace(&self, namespace: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_namespace = CString::new(namespace)?;
+        unsafe { ffi::gnc_commodity_set_namespace(self.ptr.as_ptr(), c_namespace.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the full name.
+    pub fn set_fullname(&self, fullname: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_fullname = CString::new(fullname)?;
+        unsafe { ffi::gnc_commodity_set_fullname(self.ptr.as_ptr(), c_fullname.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the CUSIP or other exchange code.
+    pub fn set_cusip(&self, cusip: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_cusip = CString::new(cusip)?;
+        unsafe { ffi::gnc_commodity_set_cusip(self.ptr.as_ptr(), c_cusip.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the smallest tradable fraction.
+    pub fn set_fraction(&self, fraction: i32) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gnc_commodity_set_fraction(self.ptr.as_ptr(), fraction) }
+        Ok(())
+    }
+
+    /// Enables or disables online price quotes.
+    pub fn set_quote_flag(&self, flag: bool) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gnc_commodity_set_quote_flag(self.ptr.as_ptr(), flag as i32) }
+        Ok(())
+    }
+
+    /// Sets the online price quote source.
+    pub fn set_quote_source(&self, source: &QuoteSource) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gnc_commodity_set_quote_source(self.ptr.as_ptr(), source.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the time zone used for price quotes.
+    pub fn set_quote_tz(&self, tz: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_tz = CString::new(tz)?;
+        unsafe { ffi::gnc_commodity_set_quote_tz(self.ptr.as_ptr(), c_tz.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the display symbol.
+    pub fn set_user_symbol(&self, symbol: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_symbol = CString::new(symbol)?;
+        unsafe { ffi::gnc_commodity_set_user_symbol(self.ptr.as_ptr(), c_symbol.as_ptr()) }
+        Ok(())
+    }
+}
+
+/// The engine cannot roll back commodities, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Commodity<'_> {
+    fn begin_edit(&self) {
+        Commodity::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Commodity::commit_edit(self)
+    }
+}
+
+impl Drop for Commodity<'_> {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe { ffi::gnc_commodity_destroy(self.ptr.as_ptr()) }
+        }
+    }
+}
+
+impl std::fmt::Debug for Commodity<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commodity")
+            .field("namespace", &self.namespace())
+            .field("mnemonic", &self.mnemonic())
+            .field("fraction", &self.fraction())
+            .finish()
+    }
+}
+
+impl PartialEq for Commodity<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { ffi::gnc_commodity_equiv(self.ptr.as_ptr(), other.ptr.as_ptr()) != 0 }
+    }
+}
+
+impl Eq for Commodity<'_> {}
+
+impl std::hash::Hash for Commodity<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Commodities are equivalent when namespace and mnemonic match
+        self.unique_name().hash(state);
+    }
+}
+
+/// An online price quote source (a Finance::Quote method).
+///
+/// Quote sources are global to the engine and never freed.
+#[derive(Clone, Copy)]
+pub struct QuoteSource {
+    ptr: NonNull<ffi::gnc_quote_source>,
+}
+
+impl QuoteSource {
+    /// Looks up a quote source by its internal name, e.g. "currency" or "yahoo_json".
+    pub fn lookup(name: &str) -> Option<Self> {
+        let c_name = CString::new(name).ok()?;
+        unsafe { Self::from_raw(ffi::gnc_quote_source_lookup_by_internal(c_name.as_ptr())) }
+    }
+
+    /// Creates a QuoteSource wrapper from a raw pointer.
+    ///
+    /// # Safety
+    /// The pointer must be valid and point to a gnc_quote_source.
+    pub unsafe fn from_raw(ptr: *mut ffi::gnc_quote_source) -> Option<Self> {
+        NonNull::new(ptr).map(|ptr| Self { ptr })
+    }
+
+    /// Returns the raw pointer to the underlying gnc_quote_source.
+    pub fn as_ptr(&self) -> *mut ffi::gnc_quote_source {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns the internal (Finance::Quote) name.
+    pub fn internal_name(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_quote_source_get_internal_name(self.ptr.as_ptr())) }
+    }
+
+    /// Returns the user-visible name.
+    pub fn user_name(&self) -> Option<String> {
+        unsafe { opt_string(ffi::gnc_quote_source_get_user_name(self.ptr.as_ptr())) }
+    }
+
+    /// Returns true if the installed Finance::Quote supports this source.
+    pub fn is_supported(&self) -> bool {
+        unsafe { ffi::gnc_quote_source_get_supported(self.ptr.as_ptr()) != 0 }
+    }
+}
+
+impl std::fmt::Debug for QuoteSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuoteSource")
+            .field("internal_name", &self.internal_name())
+            .finish()
+    }
+}
+
+impl PartialEq for QuoteSource {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl Eq for QuoteSource {}
+
+/// A GnuCash CommodityTable - the per-book registry of commodities.
+pub struct CommodityTable<'book> {
+    ptr: NonNull<ffi::gnc_commodity_table>,
+    book: &'book Book,
+}
+
+impl<'book> CommodityTable<'book> {
+    /// Gets the commodity table for a book.
+    pub fn get_table(book: &'book Book) -> Option<Self> {
+        unsafe {
+            let ptr = ffi::gnc_commodity_table_get_table(book.as_ptr());
+            NonNull::new(ptr).map(|ptr| Self {
+                ptr,
+                book,
+            })
+        }
+    }
+
+    /// Returns the raw pointer to the underlying gnc_commodity_table.
+    pub fn as_ptr(&self) -> *mut ffi::gnc_commodity_table {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns the number of commodities in the table.
+    pub fn len(&self) -> usize {
+        unsafe { ffi::gnc_commodity_table_get_size(self.ptr.as_ptr()) as usize }
+    }
+
+    /// Returns true if the table contains no commodities.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds the standard namespaces and all ISO 4217 currencies.
+    pub fn add_default_data(&self) -> bool {
+        unsafe {
+            ffi::gnc_commodity_table_add_default_data(self.ptr.as_ptr(), self.book.as_ptr()) != 0
+        }
+    }
+
+    // ==================== Lookup ====================
+
+    /// Looks up a commodity by namespace and mnemonic.
+    pub fn lookup(&self, namespace: &str, mnemonic: &str) -> Option<Commodity<'book>> {
+        let c_namespace = CString::new(namespace).ok()?;
+        let c_mnemonic = CString::new(mnemonic).ok()?;
+        unsafe {
+            let ptr = ffi::gnc_commodity_table_lookup(
+                self.ptr.as_ptr(),
+                c_namespace.as_ptr(),
+                c_mnemonic.as_ptr(),
+            );
+            Commodity::from_raw(ptr, false)
+        }
+    }
+
+    /// Looks up a currency by ISO code, e.g. "USD".
+    pub fn lookup_currency(&self, code: &str) -> Option<Commodity<'book>> {
+        self.lookup(namespace::CURRENCY, code)
+    }
+
+    /// Looks up a commodity by unique name ("namespace::mnemonic").
+    pub fn lookup_unique(&self, unique_name: &str) -> Option<Commodity<'book>> {
+        let c_name = CString::new(unique_name).ok()?;
+        unsafe {
+            let ptr = ffi::gnc_commodity_table_lookup_unique(self.ptr.as_ptr(), c_name.as_ptr());
+            Commodity::from_raw(ptr, false)
+        }
+    }
+
+    /// Finds a commodity by namespace and full name.
+    pub fn find_full(&self, namespace: &str, fullname: &str) -> Option<Commodity<'book>> {
+        let c_namespace = CString::new(namespace).ok()?;
+        let c_fullname = CString::new(fullname).ok()?;
+        unsafe {
+            let ptr = ffi::gnc_commodity_table_find_full(
+                self.ptr.as_ptr(),
+                c_namespace.as_ptr(),
+                c_fullname.as_ptr(),
+            );
+            Commodity::from_raw(ptr, false)
+        }
+    }
+
+    // ==================== Modification ====================
+
+    /// Inserts a commodity into the table, transferring ownership to the book.
+    ///
+    /// If a commodity with the same namespace and mnemonic already exists,
+    /// it is updated from `commodity` (which is then destroyed by the engine)
+    /// and the existing commodity is returned.
+    pub fn insert(&self, mut commodity: Commodity) -> Commodity<'book> {
+        commodity.mark_unowned();
+        unsafe {
+            let ptr = ffi::gnc_commodity_table_insert(self.ptr.as_ptr(), commodity.as_ptr());
+            Commodity::from_raw(ptr, false).expect("gnc_commodity_table_insert returned null")
+        }
+    }
+
+    /// Removes a commodity from the table.
+    ///
+    /// The commodity is not destroyed: the returned handle does not own it,
+    /// and it can be given back to the table with [`insert`](Self::insert).
+    ///
+    /// # Errors
+    /// * [`Error::InUse`](crate::Error::InUse) if accounts, transactions,
+    ///   prices or other objects still use the commodity (see
+    ///   [`Commodity::referrers`]).
+    pub fn remove(&self, commodity: Commodity<'book>) -> Result<Commodity<'book>> {
+        let referrers = commodity.referrers();
+        if !referrers.is_empty() {
+            return Err(in_use("commodity", referrers));
+        }
+        unsafe { ffi::gnc_commodity_table_remove(self.ptr.as_ptr(), commodity.as_ptr()) }
+        Ok(commodity)
+    }
+
+    // ==================== Namespaces ====================
+
+    /// Returns the names of all namespaces in the table.
+    pub fn namespaces(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        unsafe {
+            let list = ffi::gnc_commodity_table_get_namespaces(self.ptr.as_ptr());
+            let mut node = list;
+            while !node.is_null() {
+                if let Some(name) = opt_string((*node).data as *const std::os::raw::c_char) {
+                    names.push(name);
+                }
+                node = (*node).next;
+            }
+            ffi::g_list_free(list);
+        }
+        names
+    }
+
+    /// Returns true if the namespace exists.
+    pub fn has_namespace(&self, namespace: &str) -> bool {
+        let Ok(c_namespace) = CString::new(namespace) else {
+            return false;
+        };
+        unsafe {
+            ffi::gnc_commodity_table_has_namespace(self.ptr.as_ptr(), c_namespace.as_ptr()) != 0
+        }
+    }
+
+    /// Adds a namespace. Does nothing if it already exists.
+    ///
+    /// Returns [`Error::StringConversion`](crate::Error::StringConversion)
+    /// if `namespace` contains a null byte.
+    pub fn add_namespace(&self, namespace: &str) -> Result<()> {
+        let c_namespace = CString::new(namespace)?;
+        unsafe {
+            ffi::gnc_commodity_table_add_namespace(
+                self.ptr.as_ptr(),
+                c_namespace.as_ptr(),
+                self.book.as_ptr(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Deletes a namespace and destroys all commodities in it.
+    ///
+    /// Returns [`Error::StringConversion`](crate::Error::StringConversion)
+    /// if `namespace` contains a null byte.
+    ///
+    /// # Safety
+    /// The commodities in the namespace are freed. No account, transaction,
+    /// price or other object may still use one of them (see
+    /// [`Commodity::referrers`]), and no [`Commodity`] handle to one of them
+    /// may be used afterwards.
+    pub unsafe fn delete_namespace(&self, namespace: &str) -> Result<()> {
+        let c_namespace = CString::new(namespace)?;
+        unsafe {
+            ffi::gnc_commodity_table_delete_namespace(self.ptr.as_ptr(), c_namespace.as_ptr())
+        }
+        Ok(())
+    }
+
+    // ==================== Iteration ====================
+
+    /// Returns all commodities in the table.
+    pub fn commodities(&self) -> Vec<Commodity<'book>> {
+        unsafe extern "C" fn collect(cm: *mut ffi::gnc_commodity, data: ffi::gpointer) -> i32 {
+            let out = unsafe { &mut *(data as *mut Vec<Commodity>) };
+            if let Some(commodity) = unsafe { Commodity::from_raw(cm, false) } {
+                out.push(commodity);
+            }
+            1
+        }
+
+        let mut out: Vec<Commodity> = Vec::new();
+        unsafe {
+            ffi::gnc_commodity_table_foreach_commodity(
+                self.ptr.as_ptr(),
+                Some(collect),
+                &mut out as *mut Vec<Commodity> as ffi::gpointer,
+            );
+        }
+        out
+    }
+
+    /// Returns all commodities in the given namespace.
+    ///
+    /// A namespace with a null byte cannot exist, so it has no commodities.
+    pub fn commodities_in(&self, namespace: &str) -> Vec<Commodity<'book>> {
+        let Ok(c_namespace) = CString::new(namespace) else {
+            return Vec::new();
+        };
+        unsafe {
+            let list =
+                ffi::gnc_commodity_table_get_commodities(self.ptr.as_ptr(), c_namespace.as_ptr());
+            take_commodity_list(list)
+        }
+    }
+
+    /// Returns the commodities flagged for online price quotes whose quote
+    /// source is also supported; flagged commodities with an unsupported or
+    /// missing source are left out.
+    pub fn quotable_commodities(&self) -> Vec<Commodity<'book>> {
+        unsafe {
+            let list = ffi::gnc_commodity_table_get_quotable_commodities(self.ptr.as_ptr());
+            take_commodity_list(list)
+        }
+    }
+}
+
+impl<'book> IntoIterator for &CommodityTable<'book> {
+    type Item = Commodity<'book>;
+    type IntoIter = std::vec::IntoIter<Commodity<'book>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.commodities().into_iter()
+    }
+}
+
+impl std::fmt::Debug for CommodityTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommodityTable")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Converts an engine-owned C string into an owned String.
+unsafe fn opt_string(ptr: *const std::os::raw::c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+    }
+}
+
+/// Collects a caller-owned GList of commodities and frees the list.
+unsafe fn take_commodity_list<'book>(list: *mut ffi::GList) -> Vec<Commodity<'book>> {
+    let mut out = Vec::new();
+    let mut node = list;
+    while !node.is_null() {
+        unsafe {
+            if let Some(commodity) = Commodity::from_raw((*node).data as *mut _, false) {
+                out.push(commodity);
+            }
+            node = (*node).next;
+        }
+    }
+    unsafe { ffi::g_list_free(list) };
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commodity_table_defaults() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        assert!(table.has_namespace(namespace::CURRENCY));
+
+        let usd = table.lookup_currency("USD").unwrap();
+        assert!(usd.is_currency());
+        assert_eq!(usd.mnemonic().as_deref(), Some("USD"));
+        assert_eq!(usd.fraction(), 100);
+
+        assert!(table.lookup(namespace::CURRENCY, "US\0D").is_none());
+        assert!(!table.has_namespace("bad\0space"));
+        assert!(table.add_namespace("bad\0space").is_err());
+        assert!(Commodity::new(&book, "Bad", "bad\0space", "BAD", None, 100).is_err());
+    }
+
+    #[test]
+    fn test_commodity_insert_and_remove() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        let acme =
+            Commodity::new(&book, "Acme Corp", namespace::NYSE, "ACME", None, 10000).unwrap();
+        let acme = table.insert(acme);
+        assert!(!acme.is_currency());
+        assert_eq!(table.lookup(namespace::NYSE, "ACME"), Some(acme));
+        assert_eq!(table.commodities_in(namespace::NYSE).len(), 1);
+
+        let acme = table.lookup(namespace::NYSE, "ACME").unwrap();
+        let acme = table.remove(acme).unwrap();
+        assert!(table.lookup(namespace::NYSE, "ACME").is_none());
+        let acme = table.insert(acme);
+        assert_eq!(table.lookup(namespace::NYSE, "ACME"), Some(acme));
+    }
+
+    #[test]
+    fn test_commodity_remove_refuses_used_commodity() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        let acme =
+            Commodity::new(&book, "Acme Corp", namespace::NYSE, "ACME", None, 10000).unwrap();
+        let acme = table.insert(acme);
+        let shares = book
+            .ensure_account("Assets:Acme", crate::GNCAccountType::ACCT_TYPE_STOCK)
+            .unwrap();
+        shares.edit_with(|a| a.set_commodity(&acme)).unwrap();
+
+        assert_eq!(acme.referrers().len(), 1);
+        assert!(matches!(
+            table.remove(acme),
+            Err(crate::Error::InUse { entity: "commodity", .. })
+        ));
+        assert!(table.lookup(namespace::NYSE, "ACME").is_some());
+    }
+}
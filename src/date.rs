@@ -0,0 +1,413 @@
+//! Typed date and time values for GnuCash.
+//!
+//! The engine stores timestamps as `time64` (seconds since the Unix epoch).
+//! [`Time64`] wraps that value and [`GncDate`] represents a calendar day.
+
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::ffi;
+use crate::{Error, Result};
+
+/// Size of the buffer handed to `gnc_time64_to_iso8601_buff`.
+const ISO_DATE_BUFFER: usize = 64;
+
+/// A point in time, in seconds since the Unix epoch.
+///
+/// # Examples
+///
+/// ```ignore
+/// use gnucash_sys::{GncDate, Time64};
+///
+/// // Start of day in local time
+/// let t = Time64::from_dmy(15, 1, 2024)?;
+///
+/// // Parse an ISO 8601 timestamp
+/// let parsed = Time64::parse_iso8601("2024-01-15 10:59:00")?;
+/// assert_eq!(parsed.date(), GncDate::from_dmy(15, 1, 2024)?);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Time64(ffi::time64);
+
+impl Time64 {
+    /// Creates a Time64 from seconds since the Unix epoch.
+    pub const fn from_secs(secs: i64) -> Self {
+        Self(secs)
+    }
+
+    /// Returns the number of seconds since the Unix epoch.
+    pub const fn secs(&self) -> i64 {
+        self.0
+    }
+
+    /// Returns the current time.
+    pub fn now() -> Self {
+        Self(unsafe { ffi::gnc_time(std::ptr::null_mut()) })
+    }
+
+    /// Returns the first second of the given day in local time.
+    pub fn from_dmy(day: u32, month: u32, year: i32) -> Result<Self> {
+        GncDate::from_dmy(day, month, year).map(|d| d.day_start())
+    }
+
+    /// Returns the last second of the given day in local time.
+    pub fn from_dmy_end(day: u32, month: u32, year: i32) -> Result<Self> {
+        GncDate::from_dmy(day, month, year).map(|d| d.day_end())
+    }
+
+    /// Returns the timezone-neutral time (10:59 UTC) of the given day.
+    ///
+    /// This is what GnuCash uses for transaction post dates.
+    pub fn from_dmy_neutral(day: u32, month: u32, year: i32) -> Result<Self> {
+        GncDate::from_dmy(day, month, year).map(|d| d.day_neutral())
+    }
+
+    /// Parses an ISO 8601 timestamp ("2024-01-15 10:59:00" or with a zone offset).
+    pub fn parse_iso8601(s: &str) -> Result<Self> {
+        let c_str = CString::new(s).map_err(|e| Error::StringConversion(e.to_string()))?;
+        let secs = unsafe { ffi::gnc_iso8601_to_time64_gmt(c_str.as_ptr()) };
+        // The engine signals parse failures with INT64_MAX.
+        if secs == i64::MAX {
+            Err(Error::InvalidOperation(format!("invalid ISO 8601 date: {:?}", s)))
+        } else {
+            Ok(Self(secs))
+        }
+    }
+
+    /// Formats this time as an ISO 8601 string in UTC.
+    pub fn to_iso8601(&self) -> String {
+        let mut buf = [0 as std::os::raw::c_char; ISO_DATE_BUFFER];
+        unsafe {
+            let end = ffi::gnc_time64_to_iso8601_buff(self.0, buf.as_mut_ptr());
+            if end.is_null() {
+                return String::new();
+            }
+            CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+        }
+    }
+
+    /// Returns the first second of this time's day in local time.
+    pub fn day_start(&self) -> Self {
+        Self(unsafe { ffi::gnc_time64_get_day_start(self.0) })
+    }
+
+    /// Returns the last second of this time's day in local time.
+    pub fn day_end(&self) -> Self {
+        Self(unsafe { ffi::gnc_time64_get_day_end(self.0) })
+    }
+
+    /// Returns the timezone-neutral time (10:59 UTC) of this time's day.
+    pub fn day_neutral(&self) -> Self {
+        Self(unsafe { ffi::gnc_time64_get_day_neutral(self.0) })
+    }
+
+    /// Returns the calendar day of this time in local time.
+    pub fn date(&self) -> GncDate {
+        let gdate = unsafe { ffi::time64_to_gdate(self.0) };
+        GncDate::from_gdate(&gdate)
+    }
+}
+
+impl fmt::Debug for Time64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Time64({})", self.0)
+    }
+}
+
+impl fmt::Display for Time64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_iso8601())
+    }
+}
+
+impl FromStr for Time64 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Time64::parse_iso8601(s)
+    }
+}
+
+impl From<i64> for Time64 {
+    fn from(secs: i64) -> Self {
+        Self(secs)
+    }
+}
+
+impl From<Time64> for i64 {
+    fn from(t: Time64) -> Self {
+        t.0
+    }
+}
+
+/// A calendar date (day, month, year) without a time of day.
+///
+/// The fields are validated on construction, so a `GncDate` always
+/// names a real day.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GncDate {
+    // Field order gives chronological ordering for the derived Ord.
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl GncDate {
+    /// Creates a date from day, month (1-12) and year.
+    ///
+    /// Returns an error if the combination is not a valid calendar day.
+    pub fn from_dmy(day: u32, month: u32, year: i32) -> Result<Self> {
+        let valid = u8::try_from(day).is_ok()
+            && u16::try_from(year).is_ok()
+            && unsafe { ffi::g_date_valid_dmy(day as u8, month, year as u16) != 0 };
+        if valid {
+            Ok(Self { year, month, day })
+        } else {
+            Err(Error::InvalidOperation(format!(
+                "invalid date: {:02}/{:02}/{:04}",
+                day, month, year
+            )))
+        }
+    }
+
+    /// Returns today's date in local time.
+    pub fn today() -> Self {
+        Time64::now().date()
+    }
+
+    pub(crate) fn from_gdate(gdate: &ffi::GDate) -> Self {
+        unsafe {
+            Self {
+                year: ffi::g_date_get_year(gdate) as i32,
+                month: ffi::g_date_get_month(gdate),
+                day: ffi::g_date_get_day(gdate) as u32,
+            }
+        }
+    }
+
+    /// Returns the day of the month (1-31).
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Returns the month (1-12).
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// Returns the year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the first second of this day in local time.
+    pub fn day_start(&self) -> Time64 {
+        Time64(unsafe { ffi::gnc_dmy2time64(self.day as i32, self.month as i32, self.year) })
+    }
+
+    /// Returns the last second of this day in local time.
+    pub fn day_end(&self) -> Time64 {
+        Time64(unsafe { ffi::gnc_dmy2time64_end(self.day as i32, self.month as i32, self.year) })
+    }
+
+    /// Returns the timezone-neutral time (10:59 UTC) of this day.
+    pub fn day_neutral(&self) -> Time64 {
+        Time64(unsafe {
+            ffi::gnc_dmy2time64_neutral(self.day as i32, self.month as i32, self.year)
+        })
+    }
+}
+
+impl fmt::Debug for GncDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GncDate({})", self)
+    }
+}
+
+impl fmt::Display for GncDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for GncDate {
+    type Err = Error;
+
+    /// Parses a date in "YYYY-MM-DD" form.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidOperation(format!("invalid date: {:?}", s));
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let month = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let day = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        GncDate::from_dmy(day, month, year)
+    }
+}
+
+impl From<GncDate> for Time64 {
+    /// Converts to the timezone-neutral time of the day.
+    fn from(date: GncDate) -> Self {
+        date.day_neutral()
+    }
+}
+
+// ==================== chrono Support ====================
+
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use super::*;
+    use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+
+    impl From<Time64> for DateTime<Utc> {
+        fn from(t: Time64) -> Self {
+            Utc.timestamp_opt(t.secs(), 0)
+                .single()
+                .unwrap_or(DateTime::<Utc>::MAX_UTC)
+        }
+    }
+
+    impl<Tz: TimeZone> From<DateTime<Tz>> for Time64 {
+        fn from(dt: DateTime<Tz>) -> Self {
+            Time64::from_secs(dt.timestamp())
+        }
+    }
+
+    impl From<GncDate> for NaiveDate {
+        fn from(d: GncDate) -> Self {
+            NaiveDate::from_ymd_opt(d.year(), d.month(), d.day())
+                .expect("GncDate is always a valid date")
+        }
+    }
+
+    impl TryFrom<NaiveDate> for GncDate {
+        type Error = Error;
+
+        fn try_from(d: NaiveDate) -> Result<Self> {
+            GncDate::from_dmy(d.day(), d.month(), d.year())
+        }
+    }
+}
+
+// ==================== time Support ====================
+
+#[cfg(feature = "time")]
+mod time_impl {
+    use super::*;
+
+    impl TryFrom<Time64> for time::OffsetDateTime {
+        type Error = Error;
+
+        fn try_from(t: Time64) -> Result<Self> {
+            time::OffsetDateTime::from_unix_timestamp(t.secs())
+                .map_err(|e| Error::InvalidOperation(e.to_string()))
+        }
+    }
+
+    impl From<time::OffsetDateTime> for Time64 {
+        fn from(dt: time::OffsetDateTime) -> Self {
+            Time64::from_secs(dt.unix_timestamp())
+        }
+    }
+
+    impl TryFrom<GncDate> for time::Date {
+        type Error = Error;
+
+        fn try_from(d: GncDate) -> Result<Self> {
+            let month = time::Month::try_from(d.month() as u8)
+                .map_err(|e| Error::InvalidOperation(e.to_string()))?;
+            time::Date::from_calendar_date(d.year(), month, d.day() as u8)
+                .map_err(|e| Error::InvalidOperation(e.to_string()))
+        }
+    }
+
+    impl TryFrom<time::Date> for GncDate {
+        type Error = Error;
+
+        fn try_from(d: time::Date) -> Result<Self> {
+            GncDate::from_dmy(d.day() as u32, d.month() as u32, d.year())
+        }
+    }
+}
+
+// ==================== Serde Support ====================
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    impl Serialize for Time64 {
+        fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_i64(self.secs())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Time64 {
+        fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            i64::deserialize(deserializer).map(Time64::from_secs)
+        }
+    }
+
+    impl Serialize for GncDate {
+        fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&self.to_string())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for GncDate {
+        fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(de::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gnc_date_validation() {
+        assert!(GncDate::from_dmy(29, 2, 2024).is_ok());
+        assert!(GncDate::from_dmy(29, 2, 2023).is_err());
+        assert!(GncDate::from_dmy(1, 13, 2024).is_err());
+        assert!(GncDate::from_dmy(0, 1, 2024).is_err());
+    }
+
+    #[test]
+    fn test_gnc_date_parse() {
+        let d: GncDate = "2024-01-15".parse().unwrap();
+        assert_eq!((d.day(), d.month(), d.year()), (15, 1, 2024));
+        assert_eq!(d.to_string(), "2024-01-15");
+        assert!("2024-02-30".parse::<GncDate>().is_err());
+    }
+
+    #[test]
+    fn test_time64_round_trip() {
+        let d = GncDate::from_dmy(15, 1, 2024).unwrap();
+        assert_eq!(d.day_start().date(), d);
+        assert_eq!(d.day_end().date(), d);
+        assert!(d.day_start() < d.day_end());
+    }
+
+    #[test]
+    fn test_time64_iso8601() {
+        let t = Time64::parse_iso8601("2024-01-15 10:59:00 +0000").unwrap();
+        assert_eq!(t, Time64::parse_iso8601(&t.to_iso8601()).unwrap());
+        assert!(Time64::parse_iso8601("not a date").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Scoped edit sessions for GnuCash entities.
+//!
+//! GnuCash requires changes to an entity to be bracketed by a begin/commit
+//! pair. The [`Editable`] trait wraps that cycle in an RAII guard so the
+//! entity is never left open: the guard commits when asked to, and ends the
+//! edit when it is dropped without a commit (on an early return, an error
+//! propagated with `?`, or a panic). Only transactions are rolled back at
+//! that point; other entities keep their changes. See [Rollback](#rollback).
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use gnucash_sys::{Account, Book, Editable, Transaction};
+//!
+//! let book = Book::new();
+//! let account = Account::new(&book);
+//!
+//! // Closure form: commits on Ok; accounts keep changes made before an Err
+//! account.edit_with(|a| {
+//!     a.set_name("Checking");
+//!     Ok::<_, gnucash_sys::Error>(())
+//! })?;
+//!
+//! // Guard form: a transaction is rolled back unless committed
+//! let txn = Transaction::new(&book);
+//! let guard = txn.edit();
+//! guard.set_description("Groceries");
+//! guard.commit();
+//! ```
+//!
+//! # Rollback
+//!
+//! Only transactions can discard their pending changes
+//! (`xaccTransRollbackEdit`), and only for them does [`Editable::end_edit`]
+//! roll back; [`Editable::SUPPORTS_ROLLBACK`] tells the two cases apart.
+//! The engine has no rollback for other entities, so for them `end_edit`
+//! commits: an `Err`, an early return or a panic inside an edit keeps
+//! whatever was changed before it. Do the fallible work (parsing, lookups,
+//! checks) before the first setter, as [`Book::ensure_account`](crate::Book::ensure_account)
+//! does, where partial changes matter.
+
+use std::ops::Deref;
+use std::os::raw::c_void;
+
+use crate::{ffi, Error, Result, Transaction};
+
+/// An entity with a GnuCash begin/commit edit cycle.
+pub trait Editable {
+    /// Whether [`end_edit`](Self::end_edit) rolls back pending changes.
+    /// When false, it commits them.
+    const SUPPORTS_ROLLBACK: bool = false;
+
+    /// Begins an edit session.
+    fn begin_edit(&self);
+
+    /// Commits changes made during the edit session.
+    fn commit_edit(&self);
+
+    /// Ends an edit session that is not being committed.
+    ///
+    /// Rolls back pending changes if
+    /// [`SUPPORTS_ROLLBACK`](Self::SUPPORTS_ROLLBACK) is true. The default
+    /// implementation commits, since most entity types have no rollback in
+    /// the engine.
+    fn end_edit(&self) {
+        self.commit_edit();
+    }
+
+    /// Begins an edit session and returns a guard for it.
+    ///
+    /// The guard dereferences to the entity. Call [`EditGuard::commit`] to
+    /// commit; dropping the guard without committing calls
+    /// [`end_edit`](Self::end_edit), which commits for entities without
+    /// rollback support.
+    fn edit(&self) -> EditGuard<'_, Self> {
+        EditGuard::new(self)
+    }
+
+    /// Runs `f` inside an edit session.
+    ///
+    /// The edit is committed if `f` returns `Ok`. If it returns `Err` or
+    /// panics the edit is ended with [`end_edit`](Self::end_edit). Only
+    /// where [`SUPPORTS_ROLLBACK`](Self::SUPPORTS_ROLLBACK) is true, as for
+    /// transactions, are the changes `f` made rolled back; other entities
+    /// keep them, so `f` should fail before its first setter if it can.
+    fn edit_with<R, E, F>(&self, f: F) -> std::result::Result<R, E>
+    where
+        F: FnOnce(&Self) -> std::result::Result<R, E>,
+    {
+        let guard = self.edit();
+        let value = f(&guard)?;
+        guard.commit();
+        Ok(value)
+    }
+}
+
+/// An open edit session on an entity.
+///
+/// Created by [`Editable::edit`]. Ends the edit with
+/// [`Editable::end_edit`] on drop unless [`commit`](Self::commit) has been
+/// called; that rolls back changes only where
+/// [`Editable::SUPPORTS_ROLLBACK`] is true.
+#[must_use = "dropping an EditGuard ends the edit immediately"]
+pub struct EditGuard<'a, T: Editable + ?Sized> {
+    entity: &'a T,
+    finished: bool,
+}
+
+impl<'a, T: Editable + ?Sized> EditGuard<'a, T> {
+    /// Begins an edit session on `entity`.
+    pub fn new(entity: &'a T) -> Self {
+        entity.begin_edit();
+        Self {
+            entity,
+            finished: false,
+        }
+    }
+
+    /// Commits the edit.
+    pub fn commit(mut self) {
+        self.finished = true;
+        self.entity.commit_edit();
+    }
+
+    /// Ends the edit with [`Editable::end_edit`].
+    ///
+    /// Equivalent to dropping the guard, but explicit. For entities without
+    /// rollback support the changes are committed.
+    pub fn end(mut self) {
+        self.finished = true;
+        self.entity.end_edit();
+    }
+}
+
+impl EditGuard<'_, Transaction<'_>> {
+    /// Rolls back the transaction's pending changes and ends the edit.
+    pub fn rollback(self) {
+        self.end();
+    }
+}
+
+impl<T: Editable + ?Sized> Deref for EditGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.entity
+    }
+}
+
+impl<T: Editable + ?Sized> Drop for EditGuard<'_, T> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.entity.end_edit();
+        }
+    }
+}
+
+impl<T: Editable + std::fmt::Debug + ?Sized> std::fmt::Debug for EditGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EditGuard")
+            .field("entity", &self.entity)
+            .finish()
+    }
+}
+
+/// Checks that the book owning a QOF instance is writable.
+///
+/// Returns [`Error::ReadOnly`] if the book is read-only.
+///
+/// # Safety
+///
+/// `instance` must point to a valid `QofInstance` (any engine entity).
+pub unsafe fn check_book_writable(instance: *const c_void) -> Result<()> {
+    let book = unsafe { ffi::qof_instance_get_book(instance) };
+    if !book.is_null() && unsafe { ffi::qof_book_is_readonly(book) } != 0 {
+        return Err(Error::ReadOnly);
+    }
+    Ok(())
+}
+
+/// Checks that a QOF instance can be modified by a setter.
+///
+/// Returns [`Error::ReadOnly`] if its book is read-only, and
+/// [`Error::NotOpenForEdit`] (naming `entity`) if no edit session is open
+/// on it.
+///
+/// # Safety
+///
+/// `instance` must point to a valid `QofInstance` (any engine entity).
+pub unsafe fn check_editable(instance: *const c_void, entity: &'static str) -> Result<()> {
+    unsafe { check_book_writable(instance)? };
+    if unsafe { ffi::qof_instance_get_editlevel(instance) } <= 0 {
+        return Err(Error::NotOpenForEdit(entity));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, Book, Error};
+
+    #[test]
+    fn test_edit_with_commits_on_ok() {
+        let book = Book::new();
+        let account = Account::new(&book);
+        let result = account.edit_with(|a| {
+            a.set_name("Checking")?;
+            Ok::<_, Error>(42)
+        });
+        assert_eq!(result, Ok(42));
+        assert_eq!(account.name().as_deref(), Some("Checking"));
+    }
+
+    #[test]
+    fn test_edit_with_err_keeps_changes_without_rollback() {
+        let book = Book::new();
+        let account = Account::new(&book);
+
+        let result = account.edit_with(|a| {
+            a.set_name("Checking")?;
+            Err::<(), _>(Error::Unbalanced)
+        });
+        assert_eq!(result, Err(Error::Unbalanced));
+        assert_eq!(account.name().as_deref(), Some("Checking"));
+        assert_eq!(account.set_name("Savings"), Err(Error::NotOpenForEdit("account")));
+    }
+
+    #[test]
+    fn test_transaction_guard_rolls_back_on_error() {
+        let book = Book::new();
+        let txn = Transaction::new(&book);
+        txn.begin_edit();
+        txn.set_description("Original").unwrap();
+        txn.commit_edit();
+
+        let result = txn.edit_with(|t| {
+            t.set_description("Changed")?;
+            Err::<(), _>(Error::Unbalanced)
+        });
+        assert_eq!(result, Err(Error::Unbalanced));
+        assert!(!txn.is_open());
+        assert_eq!(txn.description().as_deref(), Some("Original"));
+    }
+
+    #[test]
+    fn test_transaction_guard_commit_and_drop() {
+        let book = Book::new();
+        let txn = Transaction::new(&book);
+
+        let guard = txn.edit();
+        assert!(guard.is_open());
+        guard.set_description("Kept").unwrap();
+        guard.commit();
+        assert!(!txn.is_open());
+
+        {
+            let guard = txn.edit();
+            guard.set_description("Dropped").unwrap();
+        }
+        assert!(!txn.is_open());
+        assert_eq!(txn.description().as_deref(), Some("Kept"));
+
+        let guard = txn.edit();
+        guard.set_description("Rolled back").unwrap();
+        guard.rollback();
+        assert!(!txn.is_open());
+        assert_eq!(txn.description().as_deref(), Some("Kept"));
+    }
+
+    #[test]
+    fn test_setter_requires_open_edit() {
+        let book = Book::new();
+        let account = Account::new(&book);
+        assert_eq!(account.set_name("Checking"), Err(Error::NotOpenForEdit("account")));
+
+        account.begin_edit();
+        assert!(matches!(account.set_name("bad\0name"), Err(Error::StringConversion(_))));
+        assert_eq!(account.set_name("Checking"), Ok(()));
+        account.commit_edit();
+    }
+
+    #[test]
+    fn test_setter_rejects_readonly_book() {
+        let book = Book::new();
+        let txn = Transaction::new(&book);
+        txn.begin_edit();
+        book.mark_readonly();
+        assert_eq!(txn.set_description("Late"), Err(Error::ReadOnly));
+        txn.rollback_edit();
+    }
+
+    #[test]
+    fn test_setter_rejects_readonly_transaction() {
+        let book = Book::new();
+        let txn = Transaction::new(&book);
+        txn.edit_with(|t| t.set_read_only("posted invoice")).unwrap();
+
+        txn.begin_edit();
+        assert_eq!(txn.set_notes("edited"), Err(Error::ReadOnly));
+        assert_eq!(txn.clear_read_only(), Ok(()));
+        assert_eq!(txn.set_notes("edited"), Ok(()));
+        txn.commit_edit();
+    }
+}
@@ -0,0 +1,277 @@
+//! A dedicated engine thread with an async handle.
+//!
+//! The GnuCash engine is not thread-safe. [`EngineHandle`] owns a
+//! [`Session`] on a thread of its own and runs closures there one at a time,
+//! so any number of tasks can share one book through clones of the handle.
+//!
+//! Closures receive the book and return owned data (`R: Send + 'static`):
+//! entity handles borrow the book and cannot leave the engine thread, so
+//! copy out what you need.
+//!
+//! The futures do not depend on a particular async runtime.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use gnucash_sys::{EngineHandle, SessionOptions};
+//!
+//! let engine = EngineHandle::open("/path/to/books.gnucash", SessionOptions::new()).await?;
+//!
+//! // In any task, through a clone of the handle
+//! let names: Vec<String> = engine
+//!     .run(|book| {
+//!         book.root_account()
+//!             .map(|root| root.children().filter_map(|a| a.name()).collect())
+//!             .unwrap_or_default()
+//!     })
+//!     .await?;
+//!
+//! engine.save().await?;
+//! ```
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::{Book, Error, Result, Session, SessionOptions};
+
+type Job = Box<dyn FnOnce(&mut Session) + Send>;
+
+/// A handle to a [`Session`] owned by a dedicated engine thread.
+///
+/// Cloning the handle shares the same session. The thread ends, and the
+/// session with it, when the last handle is dropped; pending work is
+/// finished first.
+#[derive(Clone)]
+pub struct EngineHandle {
+    sender: mpsc::Sender<Job>,
+}
+
+impl EngineHandle {
+    /// Starts an engine thread with a new session holding an empty book.
+    pub fn new() -> Self {
+        Self::spawn(|| Ok(Session::new()), |_| ())
+            .expect("an empty session cannot fail to open")
+    }
+
+    /// Starts an engine thread and opens the data store at `uri` on it.
+    ///
+    /// # Errors
+    /// Returns the error from [`SessionOptions::open`], or
+    /// [`Error::Session`] if the engine thread cannot be started.
+    pub async fn open(uri: &str, options: SessionOptions) -> Result<Self> {
+        let uri = uri.to_string();
+        let (reply, opened) = oneshot();
+        let handle = Self::spawn(move || options.open(&uri), move |result| reply.send(result))?;
+        match opened.await {
+            Some(Ok(())) => Ok(handle),
+            Some(Err(e)) => Err(e),
+            None => Err(stopped()),
+        }
+    }
+
+    /// Spawns the engine thread. `open` runs on it to create the session,
+    /// and `report` is told whether it succeeded.
+    fn spawn<O, F>(open: O, report: F) -> Result<Self>
+    where
+        O: FnOnce() -> Result<Session> + Send + 'static,
+        F: FnOnce(Result<()>) + Send + 'static,
+    {
+        let (sender, jobs) = mpsc::channel::<Job>();
+        thread::Builder::new()
+            .name("gnucash-engine".to_string())
+            .spawn(move || {
+                let mut session = match open() {
+                    Ok(session) => session,
+                    Err(e) => return report(Err(e)),
+                };
+                report(Ok(()));
+                for job in jobs {
+                    job(&mut session);
+                }
+            })
+            .map_err(|e| Error::Session(format!("cannot start engine thread: {}", e)))?;
+        Ok(Self { sender })
+    }
+
+    /// Runs `f` with the book on the engine thread and returns its result.
+    ///
+    /// Closures run in the order they are submitted. A panic in `f` is
+    /// resumed in the caller; the engine thread carries on.
+    ///
+    /// # Errors
+    /// Returns [`Error::Session`] if the engine thread has stopped.
+    pub async fn run<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&Book) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.run_session(move |session| {
+            let book = session.book().expect("session has a book");
+            f(book)
+        })
+        .await
+    }
+
+    /// Runs `f` with the session itself on the engine thread, for
+    /// operations such as [`Session::save_as`].
+    ///
+    /// # Errors
+    /// Returns [`Error::Session`] if the engine thread has stopped.
+    pub async fn run_session<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut Session) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (reply, result) = oneshot();
+        let job: Job = Box::new(move |session| {
+            reply.send(std::panic::catch_unwind(AssertUnwindSafe(|| f(session))));
+        });
+        self.sender.send(job).map_err(|_| stopped())?;
+        match result.await {
+            Some(Ok(value)) => Ok(value),
+            Some(Err(payload)) => std::panic::resume_unwind(payload),
+            None => Err(stopped()),
+        }
+    }
+
+    /// Saves the session on the engine thread.
+    ///
+    /// # Errors
+    /// As for [`Session::save`], or [`Error::Session`] if the engine thread
+    /// has stopped.
+    pub async fn save(&self) -> Result<()> {
+        self.run_session(|session| session.save()).await?
+    }
+}
+
+impl Default for EngineHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for EngineHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EngineHandle").finish_non_exhaustive()
+    }
+}
+
+fn stopped() -> Error {
+    Error::Session("engine thread has stopped".to_string())
+}
+
+/// State shared by a [`ReplySender`] and its [`Reply`].
+struct Slot<T> {
+    value: Option<T>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+/// Sending half of a one-shot reply from the engine thread.
+struct ReplySender<T>(Arc<Mutex<Slot<T>>>);
+
+/// Future resolving to the reply, or `None` if the sender was dropped
+/// without sending.
+struct Reply<T>(Arc<Mutex<Slot<T>>>);
+
+fn oneshot<T>() -> (ReplySender<T>, Reply<T>) {
+    let slot = Arc::new(Mutex::new(Slot {
+        value: None,
+        closed: false,
+        waker: None,
+    }));
+    (ReplySender(Arc::clone(&slot)), Reply(slot))
+}
+
+impl<T> ReplySender<T> {
+    fn send(self, value: T) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).value = Some(value);
+    }
+}
+
+impl<T> Drop for ReplySender<T> {
+    fn drop(&mut self) {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        slot.closed = true;
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Future for Reply<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(value) = slot.value.take() {
+            Poll::Ready(Some(value))
+        } else if slot.closed {
+            Poll::Ready(None)
+        } else {
+            slot.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Wake;
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_returns_owned_data() {
+        let engine = EngineHandle::new();
+        let shared = engine.clone();
+        let readonly = block_on(shared.run(|book| book.is_readonly())).unwrap();
+        assert!(!readonly);
+        let count = block_on(engine.run(|book| book.transaction_count())).unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_open_failure_is_reported() {
+        let result = block_on(EngineHandle::open(
+            "xml:///nonexistent/books.gnucash",
+            SessionOptions::new(),
+        ));
+        assert!(matches!(result, Err(Error::Backend { .. })));
+    }
+
+    #[test]
+    fn test_panic_is_resumed_in_caller() {
+        let engine = EngineHandle::new();
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            block_on(engine.run(|_| panic!("report failed")))
+        }));
+        assert!(result.is_err());
+        // The engine thread survives the panic
+        assert!(block_on(engine.run(|book| book.is_empty())).is_ok());
+    }
+}
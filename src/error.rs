@@ -20,6 +20,8 @@
 
 use std::fmt;
 
+use crate::ffi::QofBackendError;
+
 /// Error type for GnuCash operations.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
@@ -29,6 +31,8 @@ pub enum Error {
     InvalidGuid(String),
     /// An operation failed on a read-only object.
     ReadOnly,
+    /// An entity was modified outside of a `begin_edit`/`commit_edit` pair.
+    NotOpenForEdit(&'static str),
     /// The transaction is not balanced.
     Unbalanced,
     /// An invalid account type was specified.
@@ -41,6 +45,27 @@ pub enum Error {
     InvalidOperation(String),
     /// A session error occurred.
     Session(String),
+    /// The backend (file or database) reported an error.
+    Backend {
+        /// The engine's error code.
+        code: QofBackendError,
+        /// The session's message for the error, or a description of the code.
+        message: String,
+    },
+    /// No price path exists between two commodities.
+    NoExchangeRate {
+        /// Unique name of the source commodity.
+        from: String,
+        /// Unique name of the target commodity.
+        to: String,
+    },
+    /// An entity cannot be removed while other objects refer to it.
+    InUse {
+        /// The kind of entity, e.g. `"account"`.
+        entity: &'static str,
+        /// Descriptions of the objects that refer to it.
+        referrers: Vec<String>,
+    },
     /// Generic error with a message.
     Other(String),
 }
@@ -51,12 +76,20 @@ impl fmt::Display for Error {
             Error::NullPointer(ctx) => write!(f, "null pointer in {}", ctx),
             Error::InvalidGuid(s) => write!(f, "invalid GUID: {}", s),
             Error::ReadOnly => write!(f, "operation failed: object is read-only"),
+            Error::NotOpenForEdit(what) => write!(f, "{} is not open for editing", what),
             Error::Unbalanced => write!(f, "transaction is not balanced"),
             Error::InvalidAccountType(t) => write!(f, "invalid account type: {}", t),
             Error::StringConversion(s) => write!(f, "string conversion error: {}", s),
             Error::Numeric(s) => write!(f, "numeric error: {}", s),
             Error::InvalidOperation(s) => write!(f, "invalid operation: {}", s),
             Error::Session(s) => write!(f, "session error: {}", s),
+            Error::Backend { code, message } => write!(f, "backend error {:?}: {}", code, message),
+            Error::NoExchangeRate { from, to } => {
+                write!(f, "no exchange rate from {} to {}", from, to)
+            }
+            Error::InUse { entity, referrers } => {
+                write!(f, "{} is still used by {}", entity, referrers.join(", "))
+            }
             Error::Other(s) => write!(f, "{}", s),
         }
     }
@@ -64,5 +97,113 @@ impl fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// Returns the backend error code, if this is an [`Error::Backend`].
+    pub fn backend_code(&self) -> Option<QofBackendError> {
+        match self {
+            Error::Backend { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the data store is locked by another session.
+    pub fn is_locked(&self) -> bool {
+        self.backend_code() == Some(QofBackendError::ERR_BACKEND_LOCKED)
+    }
+
+    /// Returns true if the file or database does not exist.
+    pub fn is_file_not_found(&self) -> bool {
+        matches!(
+            self.backend_code(),
+            Some(QofBackendError::ERR_FILEIO_FILE_NOT_FOUND | QofBackendError::ERR_BACKEND_NO_SUCH_DB)
+        )
+    }
+
+    /// Returns true if the data was written by a newer version of GnuCash.
+    pub fn is_too_new(&self) -> bool {
+        matches!(
+            self.backend_code(),
+            Some(QofBackendError::ERR_BACKEND_TOO_NEW | QofBackendError::ERR_SQL_DB_TOO_NEW)
+        )
+    }
+}
+
+impl From<QofBackendError> for Error {
+    fn from(code: QofBackendError) -> Self {
+        Error::Backend {
+            code,
+            message: backend_error_description(code),
+        }
+    }
+}
+
+impl From<std::ffi::NulError> for Error {
+    fn from(e: std::ffi::NulError) -> Self {
+        Error::StringConversion(e.to_string())
+    }
+}
+
 /// Result type for GnuCash operations.
 pub type Result<T> = std::result::Result<T, Error>;
+
+/// Returns a short description of a backend error code.
+pub(crate) fn backend_error_description(code: QofBackendError) -> String {
+    use QofBackendError::*;
+
+    let text = match code {
+        ERR_BACKEND_NO_ERR => "no error",
+        ERR_BACKEND_NO_HANDLER => "no backend handler for this access method",
+        ERR_BACKEND_BAD_URL => "cannot parse URL",
+        ERR_BACKEND_NO_SUCH_DB => "database does not exist",
+        ERR_BACKEND_CANT_CONNECT => "cannot connect to database",
+        ERR_BACKEND_CONN_LOST => "lost connection to server",
+        ERR_BACKEND_LOCKED => "in use by another session",
+        ERR_BACKEND_STORE_EXISTS => "file exists, data would be destroyed",
+        ERR_BACKEND_READONLY => "cannot write to file or directory",
+        ERR_BACKEND_TOO_NEW => "file or database version is newer than supported",
+        ERR_BACKEND_DATA_CORRUPT => "data is corrupt",
+        ERR_BACKEND_PERM => "no permission to access the data",
+        ERR_FILEIO_FILE_NOT_FOUND => "file not found",
+        ERR_FILEIO_FILE_EMPTY => "file is empty",
+        ERR_FILEIO_FILE_TOO_OLD => "file version is too old to read",
+        ERR_FILEIO_UNKNOWN_FILE_TYPE => "unknown file type",
+        ERR_FILEIO_PARSE_ERROR => "cannot parse file",
+        ERR_FILEIO_WRITE_ERROR => "cannot write to file",
+        ERR_FILEIO_READ_ERROR => "cannot open file for reading",
+        ERR_FILEIO_FILE_EACCES => "no read access to file",
+        ERR_SQL_DB_TOO_OLD => "database is old and needs upgrading",
+        ERR_SQL_DB_TOO_NEW => "database version is newer than supported",
+        ERR_SQL_DB_BUSY => "database is busy",
+        other => return format!("{:?}", other),
+    };
+    text.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_error_predicates() {
+        let locked = Error::from(QofBackendError::ERR_BACKEND_LOCKED);
+        assert!(locked.is_locked());
+        assert!(!locked.is_file_not_found());
+        assert_eq!(locked.backend_code(), Some(QofBackendError::ERR_BACKEND_LOCKED));
+
+        assert!(Error::from(QofBackendError::ERR_FILEIO_FILE_NOT_FOUND).is_file_not_found());
+        assert!(Error::from(QofBackendError::ERR_SQL_DB_TOO_NEW).is_too_new());
+        assert!(!Error::ReadOnly.is_locked());
+        assert_eq!(Error::ReadOnly.backend_code(), None);
+    }
+
+    #[test]
+    fn test_backend_error_display() {
+        let err = Error::from(QofBackendError::ERR_FILEIO_FILE_NOT_FOUND);
+        assert_eq!(
+            err.to_string(),
+            "backend error ERR_FILEIO_FILE_NOT_FOUND: file not found"
+        );
+        let err = Error::from(QofBackendError::ERR_RPC_HOST_UNK);
+        assert_eq!(err.to_string(), "backend error ERR_RPC_HOST_UNK: ERR_RPC_HOST_UNK");
+    }
+}
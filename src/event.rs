@@ -0,0 +1,346 @@
+//! Subscriptions to engine events (QofEvent).
+//!
+//! The engine announces when entities are created, modified and destroyed,
+//! and when items are added to or removed from a container (such as splits
+//! in an account). An [`EventSubscription`] receives these as
+//! [`EngineEvent`] values, through a closure or a channel, until it is
+//! dropped.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use gnucash_sys::{EventKind, EventSubscription};
+//!
+//! let (subscription, events) = EventSubscription::channel();
+//! // ... change the book ...
+//! for event in events.try_iter() {
+//!     if event.kind == EventKind::Modify && event.entity_type == "Account" {
+//!         cache.invalidate(&event.guid);
+//!     }
+//! }
+//! drop(subscription);
+//! ```
+//!
+//! Handlers run synchronously, on the thread that made the change, inside
+//! the engine call that made it, so they must be `Send`. Events raised while
+//! the handler is already running (e.g. by a handler that edits an entity)
+//! are dropped rather than delivered re-entrantly. A panicking handler is
+//! not called again; the panic is resumed when the subscription is dropped,
+//! since unwinding through the engine would abort the process.
+
+use std::any::Any;
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::panic::AssertUnwindSafe;
+use std::sync::{mpsc, Mutex, PoisonError};
+
+use crate::{ffi, Guid};
+
+/// The kind of an engine event.
+///
+/// Mirrors the `QOF_EVENT_*` and `GNC_EVENT_*` event ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// An entity was created.
+    Create,
+    /// An entity, or one of its parts, was changed.
+    Modify,
+    /// An entity is about to be destroyed.
+    Destroy,
+    /// An entity was added to a container (e.g. a split to an account).
+    Add,
+    /// An entity was removed from a container.
+    Remove,
+    /// An item was added to a list owned by the entity (`GNC_EVENT_ITEM_ADDED`).
+    ItemAdded,
+    /// An item was removed from a list owned by the entity.
+    ItemRemoved,
+    /// An item in a list owned by the entity changed.
+    ItemChanged,
+    /// An application-defined event id.
+    Other(i32),
+}
+
+const QOF_EVENT_CREATE: c_int = 1 << 0;
+const QOF_EVENT_MODIFY: c_int = 1 << 1;
+const QOF_EVENT_DESTROY: c_int = 1 << 2;
+const QOF_EVENT_ADD: c_int = 1 << 3;
+const QOF_EVENT_REMOVE: c_int = 1 << 4;
+const GNC_EVENT_ITEM_ADDED: c_int = 1 << 8;
+const GNC_EVENT_ITEM_REMOVED: c_int = 1 << 9;
+const GNC_EVENT_ITEM_CHANGED: c_int = 1 << 10;
+
+impl EventKind {
+    /// Converts an engine event id.
+    pub fn from_raw(id: i32) -> Self {
+        match id {
+            QOF_EVENT_CREATE => EventKind::Create,
+            QOF_EVENT_MODIFY => EventKind::Modify,
+            QOF_EVENT_DESTROY => EventKind::Destroy,
+            QOF_EVENT_ADD => EventKind::Add,
+            QOF_EVENT_REMOVE => EventKind::Remove,
+            GNC_EVENT_ITEM_ADDED => EventKind::ItemAdded,
+            GNC_EVENT_ITEM_REMOVED => EventKind::ItemRemoved,
+            GNC_EVENT_ITEM_CHANGED => EventKind::ItemChanged,
+            other => EventKind::Other(other),
+        }
+    }
+
+    /// Returns the engine event id.
+    pub fn to_raw(self) -> i32 {
+        match self {
+            EventKind::Create => QOF_EVENT_CREATE,
+            EventKind::Modify => QOF_EVENT_MODIFY,
+            EventKind::Destroy => QOF_EVENT_DESTROY,
+            EventKind::Add => QOF_EVENT_ADD,
+            EventKind::Remove => QOF_EVENT_REMOVE,
+            EventKind::ItemAdded => GNC_EVENT_ITEM_ADDED,
+            EventKind::ItemRemoved => GNC_EVENT_ITEM_REMOVED,
+            EventKind::ItemChanged => GNC_EVENT_ITEM_CHANGED,
+            EventKind::Other(id) => id,
+        }
+    }
+}
+
+/// An event raised by the engine for one entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineEvent {
+    /// What happened.
+    pub kind: EventKind,
+    /// The QOF type of the entity, e.g. `"Account"`, `"Trans"`, `"Split"`,
+    /// `"gncInvoice"`.
+    pub entity_type: String,
+    /// The GUID of the entity.
+    pub guid: Guid,
+}
+
+/// The handler and the panic it raised, if any.
+///
+/// The engine may call the trampoline from any thread, and again from
+/// inside the handler; the mutex is only ever `try_lock`ed, so both cases
+/// drop the event instead of aliasing the handler.
+struct HandlerSlot {
+    handler: Box<dyn FnMut(&EngineEvent) + Send>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+/// A registered engine event handler.
+///
+/// The handler is unregistered when the subscription is dropped.
+#[must_use = "dropping an EventSubscription unregisters it immediately"]
+pub struct EventSubscription {
+    id: c_int,
+    // Boxed so the engine holds a stable pointer
+    slot: Box<Mutex<HandlerSlot>>,
+}
+
+impl EventSubscription {
+    /// Registers `handler` to be called for every engine event.
+    ///
+    /// Events raised while `handler` is running are not delivered to it.
+    pub fn new(handler: impl FnMut(&EngineEvent) + Send + 'static) -> Self {
+        crate::init_engine();
+        let slot = Box::new(Mutex::new(HandlerSlot {
+            handler: Box::new(handler),
+            panic: None,
+        }));
+        let data = (&*slot as *const Mutex<HandlerSlot>).cast_mut().cast::<c_void>();
+        let id = unsafe { ffi::qof_event_register_handler(Some(event_trampoline), data) };
+        Self { id, slot }
+    }
+
+    /// Registers a handler that sends every engine event to the returned
+    /// receiver.
+    ///
+    /// Events are dropped once the receiver is gone.
+    pub fn channel() -> (Self, mpsc::Receiver<EngineEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let subscription = Self::new(move |event| {
+            let _ = sender.send(event.clone());
+        });
+        (subscription, receiver)
+    }
+
+    /// Returns the engine's handler id.
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        unsafe { ffi::qof_event_unregister_handler(self.id) };
+        let slot = self.slot.get_mut().unwrap_or_else(PoisonError::into_inner);
+        if let Some(payload) = slot.panic.take()
+            && !std::thread::panicking()
+        {
+            std::panic::resume_unwind(payload);
+        }
+    }
+}
+
+impl std::fmt::Debug for EventSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSubscription")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+unsafe extern "C" fn event_trampoline(
+    ent: *mut ffi::QofInstance,
+    event_type: c_int,
+    handler_data: *mut c_void,
+    _event_data: *mut c_void,
+) {
+    if ent.is_null() || handler_data.is_null() {
+        return;
+    }
+    let slot = unsafe { &*handler_data.cast::<Mutex<HandlerSlot>>() };
+    // Busy means a nested event from inside the handler, or another thread
+    let Ok(mut slot) = slot.try_lock() else {
+        return;
+    };
+    if slot.panic.is_some() {
+        return;
+    }
+    let e_type = unsafe { (*ent).e_type };
+    let entity_type = if e_type.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(e_type) }.to_string_lossy().into_owned()
+    };
+    let guid = unsafe { ffi::qof_instance_get_guid(ent as *const c_void) };
+    let event = EngineEvent {
+        kind: EventKind::from_raw(event_type),
+        entity_type,
+        guid: if guid.is_null() {
+            *Guid::null()
+        } else {
+            Guid::from(unsafe { *guid })
+        },
+    };
+    // Unwinding out of an extern "C" fn aborts, so keep the panic for drop
+    if let Err(payload) = std::panic::catch_unwind(AssertUnwindSafe(|| (slot.handler)(&event))) {
+        slot.panic = Some(payload);
+    }
+}
+
+/// Suspends engine event generation until the guard is dropped.
+///
+/// Use around bulk operations to avoid a flood of events. Events raised
+/// while suspended are discarded, not queued. Suspensions nest: events
+/// resume when the last guard is dropped.
+pub fn suspend_events() -> EventSuspension {
+    unsafe { ffi::qof_event_suspend() };
+    EventSuspension { _private: () }
+}
+
+/// Guard returned by [`suspend_events`]; resumes events when dropped.
+#[must_use = "dropping an EventSuspension resumes events immediately"]
+#[derive(Debug)]
+pub struct EventSuspension {
+    _private: (),
+}
+
+impl Drop for EventSuspension {
+    fn drop(&mut self) {
+        unsafe { ffi::qof_event_resume() };
+    }
+}
+
+/// Serializes the tests that raise or suspend engine events: suspension is
+/// process-wide, so it would hide events from tests running alongside.
+#[cfg(test)]
+pub(crate) fn lock_engine_events() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, Book};
+
+    #[test]
+    fn test_event_kind_round_trip() {
+        for kind in [
+            EventKind::Create,
+            EventKind::Modify,
+            EventKind::Destroy,
+            EventKind::Add,
+            EventKind::Remove,
+            EventKind::ItemAdded,
+            EventKind::ItemRemoved,
+            EventKind::ItemChanged,
+            EventKind::Other(1 << 12),
+        ] {
+            assert_eq!(EventKind::from_raw(kind.to_raw()), kind);
+        }
+    }
+
+    #[test]
+    fn test_channel_receives_create_event() {
+        let _lock = lock_engine_events();
+        let book = Book::new();
+        let (subscription, events) = EventSubscription::channel();
+        let account = Account::new(&book);
+
+        let created = events
+            .try_iter()
+            .find(|e| e.guid == account.guid() && e.kind == EventKind::Create)
+            .expect("no create event for the account");
+        assert_eq!(created.entity_type, "Account");
+        drop(subscription);
+    }
+
+    #[test]
+    fn test_nested_events_are_dropped() {
+        let _lock = lock_engine_events();
+        let book = Book::new();
+        let (sender, created) = mpsc::channel();
+        let subscription = EventSubscription::new(move |event| {
+            if event.kind == EventKind::Create && event.entity_type == "Account" {
+                // Raises its own create event while this one is handled
+                let inner_book = Book::new();
+                let inner = Account::new(&inner_book);
+                let _ = sender.send((event.guid, inner.guid()));
+            }
+        });
+        let account = Account::new(&book);
+        drop(subscription);
+
+        let created: Vec<_> = created.try_iter().collect();
+        assert!(created.iter().any(|(guid, _)| *guid == account.guid()));
+        for (_, inner) in &created {
+            assert!(created.iter().all(|(guid, _)| guid != inner));
+        }
+    }
+
+    #[test]
+    fn test_handler_panic_resumes_on_drop() {
+        let _lock = lock_engine_events();
+        let book = Book::new();
+        let subscription = EventSubscription::new(|event| {
+            if event.kind == EventKind::Create {
+                panic!("handler failed");
+            }
+        });
+        let _account = Account::new(&book);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| drop(subscription)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_suspended_events_are_discarded() {
+        let _lock = lock_engine_events();
+        let book = Book::new();
+        let (subscription, events) = EventSubscription::channel();
+        let account = {
+            let _suspended = suspend_events();
+            Account::new(&book)
+        };
+        assert!(events.try_iter().all(|e| e.guid != account.guid()));
+        drop(subscription);
+    }
+}
@@ -1,29 +1,33 @@
 //! Iterators for GnuCash collections.
 
+use std::marker::PhantomData;
+
 use crate::ffi;
-use crate::{Account, Split, Transaction};
+use crate::{Account, Book, Split, Transaction};
 
 /// Iterator over the children of an Account.
-pub struct AccountChildren {
+pub struct AccountChildren<'book> {
     parent: *mut ffi::Account,
     index: i32,
     count: i32,
+    _book: PhantomData<&'book Book>,
 }
 
-impl AccountChildren {
+impl<'book> AccountChildren<'book> {
     /// Creates a new iterator over the children of the given account.
-    pub fn new(account: &Account) -> Self {
+    pub fn new(account: &Account<'book>) -> Self {
         let count = unsafe { ffi::gnc_account_n_children(account.as_ptr()) };
         Self {
             parent: account.as_ptr(),
             index: 0,
             count,
+            _book: PhantomData,
         }
     }
 }
 
-impl Iterator for AccountChildren {
-    type Item = Account;
+impl<'book> Iterator for AccountChildren<'book> {
+    type Item = Account<'book>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index >= self.count {
@@ -40,24 +44,24 @@ impl Iterator for AccountChildren {
     }
 }
 
-impl ExactSizeIterator for AccountChildren {}
+impl ExactSizeIterator for AccountChildren<'_> {}
 
 /// Iterator over all descendants of an Account (depth-first).
-pub struct AccountDescendants {
-    stack: Vec<AccountChildren>,
+pub struct AccountDescendants<'book> {
+    stack: Vec<AccountChildren<'book>>,
 }
 
-impl AccountDescendants {
+impl<'book> AccountDescendants<'book> {
     /// Creates a new iterator over all descendants of the given account.
-    pub fn new(account: &Account) -> Self {
+    pub fn new(account: &Account<'book>) -> Self {
         Self {
             stack: vec![AccountChildren::new(account)],
         }
     }
 }
 
-impl Iterator for AccountDescendants {
-    type Item = Account;
+impl<'book> Iterator for AccountDescendants<'book> {
+    type Item = Account<'book>;
 
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(iter) = self.stack.last_mut() {
@@ -74,26 +78,28 @@ impl Iterator for AccountDescendants {
 }
 
 /// Iterator over the splits in a Transaction.
-pub struct TransactionSplits {
+pub struct TransactionSplits<'book> {
     trans: *mut ffi::Transaction,
     index: i32,
     count: i32,
+    _book: PhantomData<&'book Book>,
 }
 
-impl TransactionSplits {
+impl<'book> TransactionSplits<'book> {
     /// Creates a new iterator over the splits of the given transaction.
-    pub fn new(trans: &Transaction) -> Self {
+    pub fn new(trans: &Transaction<'book>) -> Self {
         let count = unsafe { ffi::xaccTransCountSplits(trans.as_ptr()) };
         Self {
             trans: trans.as_ptr(),
             index: 0,
             count,
+            _book: PhantomData,
         }
     }
 }
 
-impl Iterator for TransactionSplits {
-    type Item = Split;
+impl<'book> Iterator for TransactionSplits<'book> {
+    type Item = Split<'book>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index >= self.count {
@@ -110,25 +116,29 @@ impl Iterator for TransactionSplits {
     }
 }
 
-impl ExactSizeIterator for TransactionSplits {}
+impl ExactSizeIterator for TransactionSplits<'_> {}
 
 /// Iterator over the splits in an Account.
 ///
 /// Note: This iterator walks the GList returned by xaccAccountGetSplitList.
-pub struct AccountSplits {
+pub struct AccountSplits<'book> {
     current: *mut ffi::GList,
+    _book: PhantomData<&'book Book>,
 }
 
-impl AccountSplits {
+impl<'book> AccountSplits<'book> {
     /// Creates a new iterator over the splits of the given account.
-    pub fn new(account: &Account) -> Self {
+    pub fn new(account: &Account<'book>) -> Self {
         let list = unsafe { ffi::xaccAccountGetSplitList(account.as_ptr()) };
-        Self { current: list }
+        Self {
+            current: list,
+            _book: PhantomData,
+        }
     }
 }
 
-impl Iterator for AccountSplits {
-    type Item = Split;
+impl<'book> Iterator for AccountSplits<'book> {
+    type Item = Split<'book>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.current.is_null() {
@@ -15,11 +15,14 @@
 //! - [`Split`] - Single entry in a transaction
 //! - [`Guid`] - 128-bit unique identifier
 //! - [`Numeric`] - Rational number (numerator/denominator)
+//! - [`Time64`] / [`GncDate`] - Timestamps and calendar dates
+//! - [`Commodity`] - Currency or security, registered in a [`CommodityTable`]
+//! - [`Editable`] / [`EditGuard`] - Scoped begin/commit edit sessions
 //!
 //! ## Example
 //!
 //! ```ignore
-//! use gnucash_sys::{Book, Account, Transaction, Split, Numeric, GNCAccountType};
+//! use gnucash_sys::{Book, Account, Transaction, Split, Numeric, GncDate, GNCAccountType};
 //!
 //! // Create a new book
 //! let book = Book::new();
@@ -27,28 +30,28 @@
 //! // Create accounts
 //! let root = Account::new(&book);
 //! root.begin_edit();
-//! root.set_name("Root");
-//! root.set_type(GNCAccountType::ACCT_TYPE_ROOT);
+//! root.set_name("Root")?;
+//! root.set_type(GNCAccountType::ACCT_TYPE_ROOT)?;
 //! root.commit_edit();
 //!
 //! let checking = Account::new(&book);
 //! checking.begin_edit();
-//! checking.set_name("Checking");
-//! checking.set_type(GNCAccountType::ACCT_TYPE_BANK);
+//! checking.set_name("Checking")?;
+//! checking.set_type(GNCAccountType::ACCT_TYPE_BANK)?;
 //! checking.commit_edit();
 //! root.append_child(&checking);
 //!
 //! // Create a transaction with splits
 //! let txn = Transaction::new(&book);
 //! txn.begin_edit();
-//! txn.set_description("Opening balance");
-//! txn.set_date(1, 1, 2024);
+//! txn.set_description("Opening balance")?;
+//! txn.set_date(GncDate::from_dmy(1, 1, 2024)?)?;
 //!
 //! let split = Split::new(&book);
-//! split.set_account(&checking);
-//! split.set_transaction(&txn);
-//! split.set_amount(Numeric::new(10000, 100)); // $100.00
-//! split.set_value(Numeric::new(10000, 100));
+//! split.set_account(&checking)?;
+//! split.set_transaction(&txn)?;
+//! split.set_amount(Numeric::new(10000, 100))?; // $100.00
+//! split.set_value(Numeric::new(10000, 100))?;
 //!
 //! txn.commit_edit();
 //!
@@ -73,8 +76,39 @@
 //! ## Safety
 //!
 //! The safe wrappers handle memory management via RAII (Drop trait), but the
-//! underlying GnuCash library is not thread-safe. While the wrapper types
-//! implement `Send`, concurrent access requires external synchronization.
+//! underlying GnuCash library is not thread-safe; see [Threads](#threads).
+//!
+//! Entity handles borrow the book they live in: `Account<'book>`,
+//! `Transaction<'book>`, `Split<'book>`, `Commodity<'book>` and so on cannot
+//! outlive the [`Book`] (or the [`Session`] that owns it), so a use after the
+//! book is destroyed is a compile error:
+//!
+//! ```compile_fail
+//! use gnucash_sys::{Account, Book};
+//!
+//! let account = {
+//!     let book = Book::new();
+//!     Account::new(&book)
+//! };
+//! account.name();
+//! ```
+//!
+//! Handles created with the `unsafe` `from_raw` constructors are not tied to
+//! a book; the caller must ensure they do not outlive it.
+//!
+//! ## Threads
+//!
+//! The engine keeps global state (the event handler list, the object
+//! registry) without locks. [`Session`], [`Book`], [`Account`],
+//! [`Transaction`], [`Split`], [`Price`] and [`PriceDB`] still implement
+//! `Send` for compatibility with earlier releases, but moving them between
+//! threads is only sound if the engine is never used from two threads at
+//! once, which the compiler cannot check. Newer types such as [`Commodity`]
+//! and [`CommodityTable`] are not `Send`.
+//!
+//! Prefer `EngineHandle` (with the `async` feature): it keeps the session on
+//! one dedicated thread and only lets owned results leave it. The `Send`
+//! impls may be removed in a future release.
 
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
@@ -109,6 +143,9 @@ pub mod iter;
 /// Safe Rust wrappers for core types (Guid, Numeric).
 pub mod types;
 
+/// Typed date and time values (Time64, GncDate).
+pub mod date;
+
 /// Safe wrapper for QofBook.
 pub mod book;
 
@@ -127,17 +164,46 @@ pub mod session;
 /// Safe wrappers for Price and PriceDB.
 pub mod price;
 
+/// Safe wrappers for Commodity and CommodityTable.
+pub mod commodity;
+
+/// Scoped edit sessions (RAII edit guards).
+pub mod edit;
+
+/// Inspection of data store locks.
+pub mod lock;
+
+/// Engine event subscriptions (QofEvent).
+pub mod event;
+
+/// A dedicated engine thread with an async handle.
+#[cfg(feature = "async")]
+pub mod engine;
+
+/// Routing of engine log output into the `log` crate.
+#[cfg(feature = "log")]
+pub mod logging;
+
 // Re-export main types at crate root
-pub use account::{Account, GNCAccountType};
-pub use book::Book;
+pub use account::{Account, GNCAccountType, Referrer};
+pub use book::{Book, BookRef, NameViolations};
+pub use commodity::{Commodity, CommodityTable, QuoteSource};
+pub use date::{GncDate, Time64};
+pub use edit::{EditGuard, Editable};
+#[cfg(feature = "async")]
+pub use engine::EngineHandle;
 pub use error::{Error, Result};
+pub use event::{suspend_events, EngineEvent, EventKind, EventSubscription, EventSuspension};
 pub use iter::{AccountChildren, AccountDescendants, AccountSplits, TransactionSplits};
 pub use split::Split;
 pub use transaction::Transaction;
-pub use types::{Guid, Numeric, GUID_ENCODING_LENGTH};
+pub use types::{Guid, Numeric, PrintInfo, RoundingMode, DENOM_AUTO, GUID_ENCODING_LENGTH};
 
 // Re-export session types
-pub use session::{init_engine, is_engine_initialized, QofBackendError, Session, SessionOpenMode};
+pub use lock::{LockInfo, LockStatus};
+pub use session::{
+    init_engine, is_engine_initialized, QofBackendError, Session, SessionOpenMode, SessionOptions,
+};
 
 // Re-export reconcile and txn_type constants
 pub use split::reconcile;
@@ -0,0 +1,337 @@
+//! Inspection of the locks GnuCash places on its data stores.
+//!
+//! A session that opens a data store read-write locks it so that a second
+//! session cannot write to it at the same time. The lock lives in the store
+//! itself, so it can be inspected without opening a session:
+//!
+//! * **XML** files are locked by an empty `<file>.LCK` file next to them. Its
+//!   modification time gives the age of the lock. While the lock is held the
+//!   engine also keeps a `<file>.<hostid>.<pid>.LNK` hard link to it, which
+//!   names the holder.
+//! * **SQLite** files are locked by a row in their `gnclock` table holding the
+//!   host name and process id of the holder. The table has no timestamp, so
+//!   the age is unknown. Reading it needs the `sqlite` feature, which links
+//!   against `libsqlite3`.
+//!
+//! Locks on server databases (PostgreSQL, MySQL) are not inspected.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{Error, Result};
+
+/// The lock state of a GnuCash data store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockStatus {
+    /// The data store is not locked, or does not exist.
+    Unlocked,
+    /// Another session holds the lock.
+    Locked(LockInfo),
+    /// The lock could not be inspected for this kind of data store.
+    Unknown,
+}
+
+impl LockStatus {
+    /// Returns true if the data store is known to be locked.
+    pub fn is_locked(&self) -> bool {
+        matches!(self, LockStatus::Locked(_))
+    }
+
+    /// Returns the lock details, if the data store is locked.
+    pub fn info(&self) -> Option<&LockInfo> {
+        match self {
+            LockStatus::Locked(info) => Some(info),
+            _ => None,
+        }
+    }
+}
+
+/// Details of a held lock.
+///
+/// The holder and age fields are optional because each backend records a
+/// different subset of them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LockInfo {
+    /// Host name of the holder (SQLite).
+    pub hostname: Option<String>,
+    /// Host id of the holder, as returned by `gethostid` (XML).
+    pub host_id: Option<String>,
+    /// Process id of the holder.
+    pub pid: Option<u32>,
+    /// Time since the lock was taken (XML).
+    pub age: Option<Duration>,
+    /// The file holding the lock: the `.LCK` file or the SQLite database.
+    pub path: PathBuf,
+}
+
+impl LockInfo {
+    /// Returns a human-readable description of the holder, if known.
+    ///
+    /// For example `"myhost (pid 1234)"` or `"host id 7f0101 (pid 1234)"`.
+    pub fn holder(&self) -> Option<String> {
+        let host = match (&self.hostname, &self.host_id) {
+            (Some(name), _) => Some(name.clone()),
+            (None, Some(id)) => Some(format!("host id {}", id)),
+            (None, None) => None,
+        };
+        match (host, self.pid) {
+            (Some(host), Some(pid)) => Some(format!("{} (pid {})", host, pid)),
+            (Some(host), None) => Some(host),
+            (None, Some(pid)) => Some(format!("pid {}", pid)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Inspects the lock on the data store at `uri`.
+///
+/// Accepts the same URIs as [`Session::open`](crate::Session::open): a plain
+/// path, or a `file://`, `xml://` or `sqlite3://` URI. A `file://` URI is
+/// treated as SQLite if the file has an SQLite header, as the engine does.
+pub(crate) fn lock_status(uri: &str) -> Result<LockStatus> {
+    let (scheme, path) = match uri.split_once("://") {
+        Some((scheme, path)) => (scheme, path),
+        None => ("file", uri),
+    };
+    let path = Path::new(path);
+    match scheme {
+        "xml" => xml_lock_status(path),
+        "sqlite3" => sqlite_lock_status(path),
+        "file" if is_sqlite_file(path) => sqlite_lock_status(path),
+        "file" => xml_lock_status(path),
+        _ => Ok(LockStatus::Unknown),
+    }
+}
+
+fn is_sqlite_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map(|()| &header == b"SQLite format 3\0")
+        .unwrap_or(false)
+}
+
+fn xml_lock_status(path: &Path) -> Result<LockStatus> {
+    let mut lock_file = path.as_os_str().to_owned();
+    lock_file.push(".LCK");
+    let lock_file = PathBuf::from(lock_file);
+
+    let metadata = match std::fs::metadata(&lock_file) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(LockStatus::Unlocked),
+        Err(e) => return Err(io_error(&lock_file, e)),
+    };
+    let age = metadata
+        .modified()
+        .ok()
+        .and_then(|taken| SystemTime::now().duration_since(taken).ok());
+
+    let mut info = LockInfo {
+        age,
+        ..LockInfo::default()
+    };
+    if let Some((host_id, pid)) = find_link_file(path) {
+        info.host_id = Some(host_id);
+        info.pid = Some(pid);
+    }
+    info.path = lock_file;
+    Ok(LockStatus::Locked(info))
+}
+
+/// Finds the `<file>.<hostid>.<pid>.LNK` file the XML backend links to its
+/// lock file, and returns the host id and pid from its name.
+fn find_link_file(path: &Path) -> Option<(String, u32)> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = format!("{}.", path.file_name()?.to_str()?);
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .find_map(|name| parse_link_file_name(&name, &prefix))
+}
+
+fn parse_link_file_name(name: &str, prefix: &str) -> Option<(String, u32)> {
+    let rest = name.strip_prefix(prefix)?.strip_suffix(".LNK")?;
+    let (host_id, pid) = rest.rsplit_once('.')?;
+    if host_id.is_empty() || host_id.contains('.') {
+        return None;
+    }
+    Some((host_id.to_string(), pid.parse().ok()?))
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> Error {
+    Error::Other(format!("cannot inspect lock {}: {}", path.display(), e))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn sqlite_lock_status(_path: &Path) -> Result<LockStatus> {
+    Ok(LockStatus::Unknown)
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_lock_status(path: &Path) -> Result<LockStatus> {
+    if !path.exists() {
+        return Ok(LockStatus::Unlocked);
+    }
+    let rows = sqlite::read_locks(path)?;
+    Ok(match rows.into_iter().next() {
+        Some((hostname, pid)) => LockStatus::Locked(LockInfo {
+            hostname: Some(hostname),
+            pid: u32::try_from(pid).ok(),
+            path: path.to_path_buf(),
+            ..LockInfo::default()
+        }),
+        None => LockStatus::Unlocked,
+    })
+}
+
+/// Minimal read-only access to the `gnclock` table through `libsqlite3`.
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::ffi::{CStr, CString};
+    use std::os::raw::{c_char, c_int, c_uchar, c_void};
+    use std::path::Path;
+
+    use crate::{Error, Result};
+
+    const SQLITE_OK: c_int = 0;
+    const SQLITE_ROW: c_int = 100;
+    const SQLITE_DONE: c_int = 101;
+    const SQLITE_OPEN_READONLY: c_int = 0x0000_0001;
+
+    unsafe extern "C" {
+        fn sqlite3_open_v2(
+            filename: *const c_char,
+            db: *mut *mut c_void,
+            flags: c_int,
+            vfs: *const c_char,
+        ) -> c_int;
+        fn sqlite3_close(db: *mut c_void) -> c_int;
+        fn sqlite3_errmsg(db: *mut c_void) -> *const c_char;
+        fn sqlite3_prepare_v2(
+            db: *mut c_void,
+            sql: *const c_char,
+            len: c_int,
+            stmt: *mut *mut c_void,
+            tail: *mut *const c_char,
+        ) -> c_int;
+        fn sqlite3_step(stmt: *mut c_void) -> c_int;
+        fn sqlite3_column_text(stmt: *mut c_void, col: c_int) -> *const c_uchar;
+        fn sqlite3_column_int64(stmt: *mut c_void, col: c_int) -> i64;
+        fn sqlite3_finalize(stmt: *mut c_void) -> c_int;
+    }
+
+    /// Returns the `(Hostname, PID)` rows of the `gnclock` table. A database
+    /// without the table has no lock.
+    pub(super) fn read_locks(path: &Path) -> Result<Vec<(String, i64)>> {
+        let c_path = CString::new(path.to_string_lossy().into_owned())?;
+        let mut db = std::ptr::null_mut();
+        let rc = unsafe {
+            sqlite3_open_v2(c_path.as_ptr(), &mut db, SQLITE_OPEN_READONLY, std::ptr::null())
+        };
+        if rc != SQLITE_OK {
+            let err = sqlite_error(db, path);
+            unsafe { sqlite3_close(db) };
+            return Err(err);
+        }
+
+        let sql = c"SELECT Hostname, PID FROM gnclock";
+        let mut stmt = std::ptr::null_mut();
+        let rc = unsafe {
+            sqlite3_prepare_v2(db, sql.as_ptr(), -1, &mut stmt, std::ptr::null_mut())
+        };
+        if rc != SQLITE_OK {
+            unsafe { sqlite3_close(db) };
+            return Ok(Vec::new());
+        }
+
+        let mut rows = Vec::new();
+        let result = loop {
+            match unsafe { sqlite3_step(stmt) } {
+                SQLITE_ROW => unsafe {
+                    let text = sqlite3_column_text(stmt, 0);
+                    let hostname = if text.is_null() {
+                        String::new()
+                    } else {
+                        CStr::from_ptr(text.cast()).to_string_lossy().into_owned()
+                    };
+                    rows.push((hostname, sqlite3_column_int64(stmt, 1)));
+                },
+                SQLITE_DONE => break Ok(rows),
+                _ => break Err(sqlite_error(db, path)),
+            }
+        };
+        unsafe {
+            sqlite3_finalize(stmt);
+            sqlite3_close(db);
+        }
+        result
+    }
+
+    fn sqlite_error(db: *mut c_void, path: &Path) -> Error {
+        let message = if db.is_null() {
+            "out of memory".to_string()
+        } else {
+            unsafe { CStr::from_ptr(sqlite3_errmsg(db)).to_string_lossy().into_owned() }
+        };
+        Error::Other(format!("cannot inspect lock {}: {}", path.display(), message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("gnucash-lock-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("books.gnucash")
+    }
+
+    #[test]
+    fn test_xml_unlocked() {
+        let path = temp_path("unlocked");
+        std::fs::write(&path, "<gnc-v2/>").unwrap();
+        let uri = format!("xml://{}", path.display());
+        assert_eq!(lock_status(&uri), Ok(LockStatus::Unlocked));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_xml_locked_with_link_file() {
+        let path = temp_path("locked");
+        std::fs::write(&path, "<gnc-v2/>").unwrap();
+        std::fs::write(path.with_extension("gnucash.LCK"), "").unwrap();
+        std::fs::write(path.with_extension("gnucash.7f0101.4242.LNK"), "").unwrap();
+
+        let status = lock_status(path.to_str().unwrap()).unwrap();
+        let info = status.info().expect("file should be locked");
+        assert_eq!(info.host_id.as_deref(), Some("7f0101"));
+        assert_eq!(info.pid, Some(4242));
+        assert!(info.age.is_some());
+        assert_eq!(info.holder().as_deref(), Some("host id 7f0101 (pid 4242)"));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_parse_link_file_name() {
+        let prefix = "books.gnucash.";
+        assert_eq!(
+            parse_link_file_name("books.gnucash.7f0101.99.LNK", prefix),
+            Some(("7f0101".to_string(), 99))
+        );
+        assert_eq!(parse_link_file_name("books.gnucash.LCK", prefix), None);
+        assert_eq!(parse_link_file_name("books.gnucash.x.y.LNK", prefix), None);
+        assert_eq!(parse_link_file_name("other.gnucash.1.2.LNK", prefix), None);
+    }
+
+    #[test]
+    fn test_server_backends_are_unknown() {
+        assert_eq!(lock_status("postgres://localhost/books"), Ok(LockStatus::Unknown));
+    }
+}
@@ -0,0 +1,210 @@
+//! Routing of GnuCash engine logging into the [`log`] crate.
+//!
+//! libgnucash logs through `qof_log`, which writes GLib log messages to
+//! stderr or a file. [`init_logging`] installs a GLib default log handler
+//! that turns them into `log` records instead. The record target is the qof
+//! module (GLib log domain), such as `gnc.engine` or `gnc.backend.xml`, so
+//! the usual `log` filters apply to it.
+//!
+//! Messages pass two filters: the qof level of their module, set with
+//! [`set_log_level`] (warning by default), and then the `log` logger's own.
+//! `tracing` subscribers receive the records through the `log` bridge
+//! (`tracing_log::LogTracer`).
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use gnucash_sys::logging::{self, LogLevel};
+//!
+//! env_logger::init();
+//! logging::init_engine_with_logging();
+//! logging::set_log_level("gnc.backend", LogLevel::Debug)?;
+//! ```
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_uint, c_void};
+use std::sync::Once;
+
+use crate::{ffi, init_engine, Result};
+
+/// GLib's `GLogFunc`.
+type GLogFunc = Option<
+    unsafe extern "C" fn(
+        log_domain: *const c_char,
+        log_level: c_uint,
+        message: *const c_char,
+        user_data: *mut c_void,
+    ),
+>;
+
+// Not covered by the generated bindings
+unsafe extern "C" {
+    fn g_log_set_default_handler(log_func: GLogFunc, user_data: *mut c_void) -> GLogFunc;
+}
+
+/// `G_LOG_LEVEL_MASK`: the level bits of `GLogLevelFlags`, without the
+/// recursion and fatal flags.
+const G_LOG_LEVEL_MASK: c_uint = !0b11;
+
+static LOG_INIT: Once = Once::new();
+
+/// A qof log level, from most to least severe.
+///
+/// The values are the engine's `QofLogLevel`, which are GLib log levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LogLevel {
+    /// Fatal errors (`G_LOG_LEVEL_ERROR`).
+    Fatal = 4,
+    /// Errors (`G_LOG_LEVEL_CRITICAL`).
+    Error = 8,
+    /// Warnings.
+    Warning = 16,
+    /// Normal but significant messages.
+    Message = 32,
+    /// Informational messages.
+    Info = 64,
+    /// Debug messages, including the engine's `ENTER`/`LEAVE` tracing.
+    Debug = 128,
+}
+
+impl LogLevel {
+    /// Returns the engine's `QofLogLevel` value.
+    pub fn to_raw(self) -> ffi::QofLogLevel {
+        self as ffi::QofLogLevel
+    }
+
+    /// Converts a GLib log level, ignoring the recursion and fatal flags.
+    ///
+    /// Returns `None` for a value that is not a single known level.
+    pub fn from_raw(level: c_uint) -> Option<Self> {
+        match level & G_LOG_LEVEL_MASK {
+            4 => Some(LogLevel::Fatal),
+            8 => Some(LogLevel::Error),
+            16 => Some(LogLevel::Warning),
+            32 => Some(LogLevel::Message),
+            64 => Some(LogLevel::Info),
+            128 => Some(LogLevel::Debug),
+            _ => None,
+        }
+    }
+
+    /// Returns the `log` level that records at this qof level are logged at.
+    pub fn to_log_level(self) -> log::Level {
+        match self {
+            LogLevel::Fatal | LogLevel::Error => log::Level::Error,
+            LogLevel::Warning => log::Level::Warn,
+            LogLevel::Message | LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+        }
+    }
+}
+
+/// Routes engine and GLib log messages into the `log` crate.
+///
+/// Initializes `qof_log` and replaces the GLib default log handler. Safe to
+/// call more than once; only the first call has an effect. Call it before
+/// [`init_engine`] to capture messages logged during initialization, or use
+/// [`init_engine_with_logging`].
+pub fn init_logging() {
+    LOG_INIT.call_once(|| unsafe {
+        ffi::qof_log_init();
+        g_log_set_default_handler(Some(log_handler), std::ptr::null_mut());
+    });
+}
+
+/// Routes engine logging into the `log` crate, then initializes the engine.
+pub fn init_engine_with_logging() {
+    init_logging();
+    init_engine();
+}
+
+/// Sets the qof log level of `module` and the modules below it.
+///
+/// Modules are dotted paths such as `gnc.engine` or `gnc.backend.sql`.
+///
+/// # Errors
+/// Returns [`Error::StringConversion`](crate::Error::StringConversion) if
+/// `module` contains a null byte.
+pub fn set_log_level(module: &str, level: LogLevel) -> Result<()> {
+    let c_module = CString::new(module)?;
+    unsafe { ffi::qof_log_set_level(c_module.as_ptr(), level.to_raw()) };
+    Ok(())
+}
+
+/// Returns true if `module` logs messages at `level` under its qof level.
+pub fn log_enabled(module: &str, level: LogLevel) -> bool {
+    match CString::new(module) {
+        Ok(c_module) => unsafe { ffi::qof_log_check(c_module.as_ptr(), level.to_raw()) != 0 },
+        Err(_) => false,
+    }
+}
+
+unsafe extern "C" fn log_handler(
+    log_domain: *const c_char,
+    log_level: c_uint,
+    message: *const c_char,
+    _user_data: *mut c_void,
+) {
+    let Some(level) = LogLevel::from_raw(log_level) else {
+        return;
+    };
+    let enabled = if log_domain.is_null() {
+        level <= LogLevel::Warning
+    } else {
+        unsafe { ffi::qof_log_check(log_domain, level.to_raw()) != 0 }
+    };
+    if !enabled {
+        return;
+    }
+    let target = if log_domain.is_null() {
+        "gnucash".into()
+    } else {
+        unsafe { CStr::from_ptr(log_domain) }.to_string_lossy()
+    };
+    let log_level = level.to_log_level();
+    if !log::log_enabled!(target: &target, log_level) || message.is_null() {
+        return;
+    }
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+    log::log!(target: &target, log_level, "{}", message.trim_end());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level_raw_round_trip() {
+        for level in [
+            LogLevel::Fatal,
+            LogLevel::Error,
+            LogLevel::Warning,
+            LogLevel::Message,
+            LogLevel::Info,
+            LogLevel::Debug,
+        ] {
+            assert_eq!(LogLevel::from_raw(level.to_raw()), Some(level));
+        }
+        // Recursion and fatal flags are ignored
+        assert_eq!(LogLevel::from_raw(16 | 0b10), Some(LogLevel::Warning));
+        assert_eq!(LogLevel::from_raw(16 | 32), None);
+    }
+
+    #[test]
+    fn test_log_level_mapping() {
+        assert_eq!(LogLevel::Error.to_log_level(), log::Level::Error);
+        assert_eq!(LogLevel::Warning.to_log_level(), log::Level::Warn);
+        assert_eq!(LogLevel::Message.to_log_level(), log::Level::Info);
+        assert_eq!(LogLevel::Debug.to_log_level(), log::Level::Debug);
+    }
+
+    #[test]
+    fn test_set_log_level() {
+        init_logging();
+        set_log_level("gnc.test.logging", LogLevel::Debug).unwrap();
+        assert!(log_enabled("gnc.test.logging", LogLevel::Debug));
+        set_log_level("gnc.test.logging", LogLevel::Error).unwrap();
+        assert!(!log_enabled("gnc.test.logging", LogLevel::Warning));
+        assert!(set_log_level("bad\0module", LogLevel::Info).is_err());
+    }
+}
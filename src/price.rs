@@ -1,38 +1,69 @@
 //! Safe wrappers for GnuCash Price and PriceDB.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use crate::ffi;
-use crate::{Book, Guid, Numeric};
+use crate::{edit, ffi};
+use crate::{
+    Book, Commodity, Editable, Error, Guid, Numeric, Result, RoundingMode, Time64, DENOM_AUTO,
+};
 
 /// Re-export PriceSource enum.
 pub use ffi::PriceSource;
 
 /// A GnuCash Price - a price quote for a commodity.
-pub struct Price {
+///
+/// Prices are reference counted by the engine. A wrapper with `owned == true`
+/// holds one reference and releases it on drop; [`Clone`] takes a new
+/// reference, so clones can be kept after the price is removed from its
+/// database or passed between crates freely.
+pub struct Price<'book> {
     ptr: NonNull<ffi::GNCPrice>,
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Price {}
+/// Kept for compatibility; the engine is not thread-safe. See
+/// [Threads](crate#threads).
+unsafe impl Send for Price<'_> {}
 
-impl Price {
+impl<'book> Price<'book> {
     /// Creates a new Price in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::gnc_price_create(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("gnc_price_create returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
     /// Creates a Price wrapper from a raw pointer.
     ///
+    /// If `owned` is true the wrapper adopts one reference that the caller
+    /// already holds and releases it on drop.
+    ///
     /// # Safety
     /// The pointer must be valid and point to a properly initialized GNCPrice.
     pub unsafe fn from_raw(ptr: *mut ffi::GNCPrice, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
+    }
+
+    /// Creates an owning Price wrapper from a borrowed pointer by taking a
+    /// new reference.
+    ///
+    /// # Safety
+    /// The pointer must be null or valid and point to a properly initialized GNCPrice.
+    pub unsafe fn from_raw_ref(ptr: *mut ffi::GNCPrice) -> Option<Self> {
+        let ptr = NonNull::new(ptr)?;
+        unsafe { ffi::gnc_price_ref(ptr.as_ptr()) };
+        Some(Self { ptr, owned: true, _book: PhantomData })
+    }
+
+    /// Looks up a price by GUID.
+    pub fn lookup_by_guid(guid: &Guid, book: &'book Book) -> Option<Price<'book>> {
+        unsafe { Self::from_raw_ref(ffi::gnc_price_lookup(guid.as_ffi(), book.as_ptr())) }
     }
 
     /// Returns the raw pointer to the underlying GNCPrice.
@@ -40,12 +71,31 @@ impl Price {
         self.ptr.as_ptr()
     }
 
+    /// Returns the GUID of this price.
+    pub fn guid(&self) -> Guid {
+        unsafe {
+            let instance = self.ptr.as_ptr() as *const std::ffi::c_void;
+            let guid_ptr = ffi::qof_instance_get_guid(instance);
+            if guid_ptr.is_null() {
+                Guid::from_bytes([0; 16])
+            } else {
+                Guid::from_bytes((*guid_ptr).reserved)
+            }
+        }
+    }
+
     /// Increments the reference count.
+    ///
+    /// Prefer [`Clone`], which pairs the new reference with a wrapper that
+    /// releases it.
     pub fn ref_(&self) {
         unsafe { ffi::gnc_price_ref(self.ptr.as_ptr()) }
     }
 
     /// Decrements the reference count.
+    ///
+    /// Only call this to balance an earlier [`ref_`](Self::ref_); the
+    /// reference held by an owned wrapper is released on drop.
     pub fn unref(&self) {
         unsafe { ffi::gnc_price_unref(self.ptr.as_ptr()) }
     }
@@ -61,15 +111,21 @@ impl Price {
     }
 
     /// Creates a clone of this price in the given book.
-    pub fn clone_in_book(&self, book: &Book) -> Option<Price> {
+    pub fn clone_in_book<'b>(&self, book: &'b Book) -> Option<Price<'b>> {
         unsafe {
             let ptr = ffi::gnc_price_clone(self.ptr.as_ptr(), book.as_ptr());
-            Self::from_raw(ptr, true)
+            Price::from_raw(ptr, true)
         }
     }
 
+    /// Creates a clone of this price in the given book.
+    #[deprecated(note = "use `Price::clone_in_book`")]
+    pub fn clone_for_book<'b>(&self, book: &'b Book) -> Option<Price<'b>> {
+        self.clone_in_book(book)
+    }
+
     /// Creates an inverted price (1/price).
-    pub fn invert(&self) -> Option<Price> {
+    pub fn invert(&self) -> Option<Price<'book>> {
         unsafe {
             let ptr = ffi::gnc_price_invert(self.ptr.as_ptr());
             Self::from_raw(ptr, true)
@@ -78,9 +134,19 @@ impl Price {
 
     // ==================== Getters ====================
 
+    /// Returns the commodity being priced.
+    pub fn commodity(&self) -> Option<Commodity<'book>> {
+        unsafe { Commodity::from_raw(ffi::gnc_price_get_commodity(self.ptr.as_ptr()), false) }
+    }
+
+    /// Returns the currency the price is expressed in.
+    pub fn currency(&self) -> Option<Commodity<'book>> {
+        unsafe { Commodity::from_raw(ffi::gnc_price_get_currency(self.ptr.as_ptr()), false) }
+    }
+
     /// Returns the time of this price quote.
-    pub fn time(&self) -> i64 {
-        unsafe { ffi::gnc_price_get_time64(self.ptr.as_ptr()) }
+    pub fn time(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::gnc_price_get_time64(self.ptr.as_ptr()) })
     }
 
     /// Returns the price source.
@@ -119,43 +185,88 @@ impl Price {
 
     // ==================== Setters ====================
 
+    /// Checks that this price can be modified.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "price") }
+    }
+
+    /// Sets the commodity being priced.
+    pub fn set_commodity(&self, commodity: &Commodity) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gnc_price_set_commodity(self.ptr.as_ptr(), commodity.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the currency the price is expressed in.
+    pub fn set_currency(&self, currency: &Commodity) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gnc_price_set_currency(self.ptr.as_ptr(), currency.as_ptr()) }
+        Ok(())
+    }
+
     /// Sets the time of this price quote.
-    pub fn set_time(&self, time: i64) {
-        unsafe { ffi::gnc_price_set_time64(self.ptr.as_ptr(), time) }
+    pub fn set_time(&self, time: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::gnc_price_set_time64(self.ptr.as_ptr(), time.secs()) }
+        Ok(())
     }
 
     /// Sets the price source.
-    pub fn set_source(&self, source: PriceSource) {
+    pub fn set_source(&self, source: PriceSource) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gnc_price_set_source(self.ptr.as_ptr(), source) }
+        Ok(())
     }
 
     /// Sets the price source from a string.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `source` contains a null byte.
-    pub fn set_source_string(&self, source: &str) {
-        let c_source = CString::new(source).unwrap();
+    pub fn set_source_string(&self, source: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_source = CString::new(source)?;
         unsafe { ffi::gnc_price_set_source_string(self.ptr.as_ptr(), c_source.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the price type string.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `type_str` contains a null byte.
-    pub fn set_type_string(&self, type_str: &str) {
-        let c_type = CString::new(type_str).unwrap();
+    pub fn set_type_string(&self, type_str: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_type = CString::new(type_str)?;
         unsafe { ffi::gnc_price_set_typestr(self.ptr.as_ptr(), c_type.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the price value.
-    pub fn set_value(&self, value: Numeric) {
+    pub fn set_value(&self, value: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::gnc_price_set_value(self.ptr.as_ptr(), value.into()) }
+        Ok(())
     }
 }
 
-impl Drop for Price {
+impl Clone for Price<'_> {
+    /// Returns a new wrapper holding its own reference to the same price.
+    fn clone(&self) -> Self {
+        self.ref_();
+        Self {
+            ptr: self.ptr,
+            owned: true,
+            _book: PhantomData,
+        }
+    }
+}
+
+/// The engine cannot roll back prices, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for Price<'_> {
+    fn begin_edit(&self) {
+        Price::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Price::commit_edit(self)
+    }
+}
+
+impl Drop for Price<'_> {
     fn drop(&mut self) {
         if self.owned {
             unsafe { ffi::gnc_price_unref(self.ptr.as_ptr()) }
@@ -163,9 +274,10 @@ impl Drop for Price {
     }
 }
 
-impl std::fmt::Debug for Price {
+impl std::fmt::Debug for Price<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Price")
+            .field("guid", &self.guid())
             .field("time", &self.time())
             .field("source", &self.source())
             .field("value", &self.value())
@@ -173,15 +285,15 @@ impl std::fmt::Debug for Price {
     }
 }
 
-impl PartialEq for Price {
+impl PartialEq for Price<'_> {
     fn eq(&self, other: &Self) -> bool {
         unsafe { ffi::gnc_price_equal(self.ptr.as_ptr(), other.ptr.as_ptr()) != 0 }
     }
 }
 
-impl Eq for Price {}
+impl Eq for Price<'_> {}
 
-impl std::hash::Hash for Price {
+impl std::hash::Hash for Price<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         // Hash by time and value since those define price identity
         self.time().hash(state);
@@ -191,28 +303,37 @@ impl std::hash::Hash for Price {
 }
 
 /// A GnuCash PriceDB - a database of price quotes.
-pub struct PriceDB {
+pub struct PriceDB<'book> {
     ptr: NonNull<ffi::GNCPriceDB>,
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for PriceDB {}
+/// Kept for compatibility; the engine is not thread-safe. See
+/// [Threads](crate#threads).
+unsafe impl Send for PriceDB<'_> {}
 
-impl PriceDB {
+impl<'book> PriceDB<'book> {
     /// Gets the price database for a book.
-    pub fn get_db(book: &Book) -> Option<Self> {
+    pub fn get(book: &'book Book) -> Option<Self> {
         unsafe {
             let ptr = ffi::gnc_pricedb_get_db(book.as_ptr());
             Self::from_raw(ptr, false)
         }
     }
 
+    /// Gets the price database for a book.
+    #[deprecated(note = "use `PriceDB::get`")]
+    pub fn get_db(book: &'book Book) -> Option<Self> {
+        Self::get(book)
+    }
+
     /// Creates a PriceDB wrapper from a raw pointer.
     ///
     /// # Safety
     /// The pointer must be valid and point to a properly initialized GNCPriceDB.
     pub unsafe fn from_raw(ptr: *mut ffi::GNCPriceDB, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer to the underlying GNCPriceDB.
@@ -246,15 +367,281 @@ impl PriceDB {
     }
 
     /// Looks up a price by GUID.
-    pub fn lookup_by_guid(guid: &Guid, book: &Book) -> Option<Price> {
+    pub fn lookup_by_guid(guid: &Guid, book: &'book Book) -> Option<Price<'book>> {
+        Price::lookup_by_guid(guid, book)
+    }
+
+    /// Returns the number of prices in the database.
+    pub fn num_prices(&self) -> usize {
+        unsafe { ffi::gnc_pricedb_get_num_prices(self.ptr.as_ptr()) as usize }
+    }
+
+    /// Checks if the database has any prices.
+    pub fn has_prices(&self) -> bool {
+        self.num_prices() > 0
+    }
+
+    // ==================== Lookup ====================
+
+    /// Returns the most recent price of `commodity` in `currency`.
+    ///
+    /// The engine matches the pair in either direction, so the result may be
+    /// a price of `currency` in `commodity`; check [`Price::commodity`]
+    /// before using its value.
+    pub fn latest(&self, commodity: &Commodity, currency: &Commodity) -> Option<Price<'book>> {
+        unsafe {
+            let ptr = ffi::gnc_pricedb_lookup_latest(
+                self.ptr.as_ptr(),
+                commodity.as_ptr(),
+                currency.as_ptr(),
+            );
+            Price::from_raw(ptr, true)
+        }
+    }
+
+    /// Returns the most recent price of `commodity` in each currency it is quoted in.
+    pub fn latest_any_currency(&self, commodity: &Commodity) -> Vec<Price<'book>> {
         unsafe {
-            let ptr = ffi::gnc_price_lookup(guid.as_ffi(), book.as_ptr());
-            Price::from_raw(ptr, false)
+            let list =
+                ffi::gnc_pricedb_lookup_latest_any_currency(self.ptr.as_ptr(), commodity.as_ptr());
+            take_price_list(list)
         }
     }
+
+    /// Returns the price of `commodity` in `currency` closest to `time`.
+    ///
+    /// Like [`latest`](Self::latest), this may return the reversed pair.
+    pub fn nearest_in_time(
+        &self,
+        commodity: &Commodity,
+        currency: &Commodity,
+        time: Time64,
+    ) -> Option<Price<'book>> {
+        unsafe {
+            let ptr = ffi::gnc_pricedb_lookup_nearest_in_time64(
+                self.ptr.as_ptr(),
+                commodity.as_ptr(),
+                currency.as_ptr(),
+                time.secs(),
+            );
+            Price::from_raw(ptr, true)
+        }
+    }
+
+    /// Returns the latest price of `commodity` in `currency` at or before `time`.
+    ///
+    /// Like [`latest`](Self::latest), this may return the reversed pair.
+    pub fn nearest_before(
+        &self,
+        commodity: &Commodity,
+        currency: &Commodity,
+        time: Time64,
+    ) -> Option<Price<'book>> {
+        unsafe {
+            let ptr = ffi::gnc_pricedb_lookup_nearest_before_t64(
+                self.ptr.as_ptr(),
+                commodity.as_ptr(),
+                currency.as_ptr(),
+                time.secs(),
+            );
+            Price::from_raw(ptr, true)
+        }
+    }
+
+    /// Returns a price of `commodity` in `currency` on the same day as `time`.
+    pub fn lookup_day(
+        &self,
+        commodity: &Commodity,
+        currency: &Commodity,
+        time: Time64,
+    ) -> Option<Price<'book>> {
+        unsafe {
+            let ptr = ffi::gnc_pricedb_lookup_day_t64(
+                self.ptr.as_ptr(),
+                commodity.as_ptr(),
+                currency.as_ptr(),
+                time.secs(),
+            );
+            Price::from_raw(ptr, true)
+        }
+    }
+
+    /// Returns true if there are any prices of `commodity` in `currency`.
+    pub fn has_prices_for(&self, commodity: &Commodity, currency: &Commodity) -> bool {
+        unsafe {
+            ffi::gnc_pricedb_has_prices(self.ptr.as_ptr(), commodity.as_ptr(), currency.as_ptr())
+                != 0
+        }
+    }
+
+    /// Returns all prices of `commodity` in `currency`, newest first.
+    pub fn prices_for(&self, commodity: &Commodity, currency: &Commodity) -> Vec<Price<'book>> {
+        unsafe {
+            let list = ffi::gnc_pricedb_get_prices(
+                self.ptr.as_ptr(),
+                commodity.as_ptr(),
+                currency.as_ptr(),
+            );
+            take_price_list(list)
+        }
+    }
+
+    /// Returns all prices of `commodity` in any currency, newest first.
+    pub fn all_prices_for(&self, commodity: &Commodity) -> Vec<Price<'book>> {
+        unsafe {
+            let list = ffi::gnc_pricedb_get_prices(
+                self.ptr.as_ptr(),
+                commodity.as_ptr(),
+                std::ptr::null(),
+            );
+            take_price_list(list)
+        }
+    }
+
+    // ==================== Conversion ====================
+
+    /// Converts `amount` of `from` into `to`.
+    ///
+    /// Uses the price nearest to `at_time`, or the latest price when `at_time`
+    /// is `None`. A direct price (`from` in `to`) is preferred, then an
+    /// inverted one (`to` in `from`), then a path through a third commodity
+    /// that both have prices with, whichever side of each price they are
+    /// on. The result is rounded to the fraction of `to`.
+    ///
+    /// Returns [`Error::NoExchangeRate`] if no price path exists.
+    pub fn convert(
+        &self,
+        amount: Numeric,
+        from: &Commodity,
+        to: &Commodity,
+        at_time: Option<Time64>,
+    ) -> Result<Numeric> {
+        let fraction = i64::from(to.fraction());
+        let rate = if from == to {
+            Some(Numeric::new(1, 1))
+        } else {
+            match self.rate(from, to, at_time) {
+                Some(rate) => Some(rate),
+                None => self.triangulated_rate(from, to, at_time)?,
+            }
+        };
+        match rate {
+            Some(rate) => amount.checked_mul(rate, fraction, RoundingMode::RoundHalfUp),
+            None => Err(Error::NoExchangeRate {
+                from: from.unique_name().unwrap_or_default(),
+                to: to.unique_name().unwrap_or_default(),
+            }),
+        }
+    }
+
+    /// Finds a price of `commodity` in `currency` for the given time.
+    fn find_price(
+        &self,
+        commodity: &Commodity,
+        currency: &Commodity,
+        at_time: Option<Time64>,
+    ) -> Option<Price<'book>> {
+        match at_time {
+            Some(t) => self.nearest_in_time(commodity, currency, t),
+            None => self.latest(commodity, currency),
+        }
+    }
+
+    /// Returns the direct or inverted exchange rate from `from` to `to`.
+    ///
+    /// The engine's lookups match the pair in either direction, so the rate
+    /// is taken from whichever way round the found price is stored.
+    fn rate(&self, from: &Commodity, to: &Commodity, at_time: Option<Time64>) -> Option<Numeric> {
+        let price = self
+            .find_price(from, to, at_time)
+            .or_else(|| self.find_price(to, from, at_time))?;
+        let value = price.value();
+        if price.commodity().as_ref() == Some(from) {
+            Some(value)
+        } else {
+            Numeric::new(1, 1)
+                .checked_div(value, DENOM_AUTO, RoundingMode::Never)
+                .ok()
+        }
+    }
+
+    /// Returns the exchange rate from `from` to `to` through a commodity
+    /// that either of them has a price with, as the priced commodity or as
+    /// the currency.
+    fn triangulated_rate(
+        &self,
+        from: &Commodity,
+        to: &Commodity,
+        at_time: Option<Time64>,
+    ) -> Result<Option<Numeric>> {
+        let is_end = |c: &Commodity| c == from || c == to;
+        let mut candidates: Vec<Commodity> = Vec::new();
+        for price in self.prices() {
+            let (Some(commodity), Some(currency)) = (price.commodity(), price.currency()) else {
+                continue;
+            };
+            let other = if is_end(&commodity) {
+                currency
+            } else if is_end(&currency) {
+                commodity
+            } else {
+                continue;
+            };
+            if !is_end(&other) && !candidates.contains(&other) {
+                candidates.push(other);
+            }
+        }
+
+        for via in &candidates {
+            if let (Some(first), Some(second)) =
+                (self.rate(from, via, at_time), self.rate(via, to, at_time))
+            {
+                return first
+                    .checked_mul(second, DENOM_AUTO, RoundingMode::Never)
+                    .map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    // ==================== Iteration ====================
+
+    /// Returns every price in the database.
+    pub fn prices(&self) -> Vec<Price<'book>> {
+        unsafe extern "C" fn collect(p: *mut ffi::GNCPrice, data: ffi::gpointer) -> i32 {
+            let out = unsafe { &mut *(data as *mut Vec<Price>) };
+            if let Some(price) = unsafe { Price::from_raw_ref(p) } {
+                out.push(price);
+            }
+            1
+        }
+
+        let mut out: Vec<Price> = Vec::new();
+        unsafe {
+            ffi::gnc_pricedb_foreach_price(
+                self.ptr.as_ptr(),
+                Some(collect),
+                &mut out as *mut Vec<Price> as ffi::gpointer,
+                1,
+            );
+        }
+        out
+    }
 }
 
-impl Drop for PriceDB {
+/// The engine cannot roll back a price database, so
+/// [`end_edit`](Editable::end_edit) commits.
+impl Editable for PriceDB<'_> {
+    fn begin_edit(&self) {
+        PriceDB::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        PriceDB::commit_edit(self)
+    }
+}
+
+impl Drop for PriceDB<'_> {
     fn drop(&mut self) {
         if self.owned {
             unsafe { ffi::gnc_pricedb_destroy(self.ptr.as_ptr()) }
@@ -262,8 +649,174 @@ impl Drop for PriceDB {
     }
 }
 
-impl std::fmt::Debug for PriceDB {
+impl std::fmt::Debug for PriceDB<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("PriceDB").finish()
+        f.debug_struct("PriceDB")
+            .field("num_prices", &self.num_prices())
+            .finish()
+    }
+}
+
+impl<'book> IntoIterator for &PriceDB<'book> {
+    type Item = Price<'book>;
+    type IntoIter = std::vec::IntoIter<Price<'book>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.prices().into_iter()
+    }
+}
+
+/// Wraps the prices of an engine PriceList and frees the list.
+///
+/// Each price in the list carries a reference owned by the caller, which
+/// is handed to the returned wrappers.
+unsafe fn take_price_list<'book>(list: *mut ffi::GList) -> Vec<Price<'book>> {
+    let mut out = Vec::new();
+    let mut node = list;
+    while !node.is_null() {
+        unsafe {
+            if let Some(price) = Price::from_raw((*node).data as *mut ffi::GNCPrice, true) {
+                out.push(price);
+            }
+            node = (*node).next;
+        }
+    }
+    unsafe { ffi::g_list_free(list) };
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GncDate;
+
+    fn add_price(db: &PriceDB, book: &Book, c: &Commodity, cur: &Commodity, day: u32, v: i64) {
+        let price = Price::new(book);
+        price
+            .edit_with(|p| {
+                p.set_commodity(c)?;
+                p.set_currency(cur)?;
+                p.set_time(GncDate::from_dmy(day, 1, 2024)?.day_neutral())?;
+                p.set_value(Numeric::new(v, 100))
+            })
+            .unwrap();
+        db.add_price(&price);
+    }
+
+    #[test]
+    fn test_pricedb_lookup() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let eur = table.lookup_currency("EUR").unwrap();
+        let usd = table.lookup_currency("USD").unwrap();
+        let db = PriceDB::get(&book).unwrap();
+
+        add_price(&db, &book, &eur, &usd, 1, 110);
+        add_price(&db, &book, &eur, &usd, 10, 112);
+        add_price(&db, &book, &eur, &usd, 20, 108);
+
+        assert_eq!(db.num_prices(), 3);
+        assert!(db.has_prices_for(&eur, &usd));
+        assert_eq!(db.latest(&eur, &usd).unwrap().value(), Numeric::new(108, 100));
+
+        let jan_12 = GncDate::from_dmy(12, 1, 2024).unwrap().day_neutral();
+        let before = db.nearest_before(&eur, &usd, jan_12).unwrap();
+        assert_eq!(before.value(), Numeric::new(112, 100));
+        assert!(db.lookup_day(&eur, &usd, jan_12).is_none());
+
+        assert_eq!(db.all_prices_for(&eur).len(), 3);
+        assert_eq!(db.prices().len(), 3);
+    }
+
+    #[test]
+    fn test_pricedb_convert() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let eur = table.lookup_currency("EUR").unwrap();
+        let usd = table.lookup_currency("USD").unwrap();
+        let gbp = table.lookup_currency("GBP").unwrap();
+        let jpy = table.lookup_currency("JPY").unwrap();
+        let db = PriceDB::get(&book).unwrap();
+
+        add_price(&db, &book, &eur, &usd, 1, 110); // 1 EUR = 1.10 USD
+        add_price(&db, &book, &gbp, &usd, 1, 125); // 1 GBP = 1.25 USD
+
+        let ten = Numeric::new(1000, 100);
+        // Direct
+        assert_eq!(db.convert(ten, &eur, &usd, None).unwrap(), Numeric::new(1100, 100));
+        // Inverted: 10 USD = 9.09 EUR
+        assert_eq!(db.convert(ten, &usd, &eur, None).unwrap(), Numeric::new(909, 100));
+        // Triangulated through USD: 10 EUR = 8.80 GBP
+        assert_eq!(db.convert(ten, &eur, &gbp, None).unwrap(), Numeric::new(880, 100));
+        // No path
+        assert!(matches!(
+            db.convert(ten, &eur, &jpy, None),
+            Err(Error::NoExchangeRate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_pricedb_convert_against_stored_direction() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let eur = table.lookup_currency("EUR").unwrap();
+        let usd = table.lookup_currency("USD").unwrap();
+        let db = PriceDB::get(&book).unwrap();
+
+        add_price(&db, &book, &eur, &usd, 1, 125); // 1 EUR = 1.25 USD only
+
+        let ten = Numeric::new(1000, 100);
+        let day = GncDate::from_dmy(2, 1, 2024).unwrap().day_neutral();
+        assert_eq!(db.convert(ten, &usd, &eur, None).unwrap(), Numeric::new(800, 100));
+        assert_eq!(db.convert(ten, &usd, &eur, Some(day)).unwrap(), Numeric::new(800, 100));
+        assert_eq!(db.convert(ten, &eur, &usd, None).unwrap(), Numeric::new(1250, 100));
+        assert_eq!(db.num_prices(), 1);
+    }
+
+    #[test]
+    fn test_pricedb_convert_via_price_currency() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let eur = table.lookup_currency("EUR").unwrap();
+        let usd = table.lookup_currency("USD").unwrap();
+        let gbp = table.lookup_currency("GBP").unwrap();
+        let db = PriceDB::get(&book).unwrap();
+
+        // GBP and USD only appear as the currency of EUR prices
+        add_price(&db, &book, &eur, &gbp, 1, 80); // 1 EUR = 0.80 GBP
+        add_price(&db, &book, &eur, &usd, 1, 125); // 1 EUR = 1.25 USD
+
+        let ten = Numeric::new(1000, 100);
+        // 10 GBP = 12.50 EUR = 15.63 USD
+        assert_eq!(db.convert(ten, &gbp, &usd, None).unwrap(), Numeric::new(1563, 100));
+        // 10 USD = 8 EUR = 6.40 GBP
+        assert_eq!(db.convert(ten, &usd, &gbp, None).unwrap(), Numeric::new(640, 100));
+    }
+
+    #[test]
+    fn test_price_refcount_and_lookup() {
+        let book = Book::new();
+        let table = book.commodity_table().unwrap();
+        table.add_default_data();
+        let eur = table.lookup_currency("EUR").unwrap();
+        let usd = table.lookup_currency("USD").unwrap();
+        let db = PriceDB::get(&book).unwrap();
+
+        add_price(&db, &book, &eur, &usd, 1, 110);
+        let price = db.latest(&eur, &usd).unwrap();
+        let found = Price::lookup_by_guid(&price.guid(), &book).unwrap();
+        assert_eq!(found.guid(), price.guid());
+
+        // Clones hold their own reference and outlive removal from the database.
+        let copy = found.clone();
+        drop(found);
+        assert!(db.remove_price(&price));
+        drop(price);
+        assert_eq!(copy.value(), Numeric::new(110, 100));
+        assert!(!db.has_prices());
     }
 }
@@ -1,11 +1,16 @@
 //! Safe wrapper for QofSession - connection to a GnuCash data store.
 
+use std::any::Any;
+use std::cell::{Cell, RefCell};
 use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::panic::AssertUnwindSafe;
 use std::ptr::NonNull;
 use std::sync::Once;
 
 use crate::ffi;
-use crate::Book;
+use crate::lock::{self, LockStatus};
+use crate::{Book, Error, Result};
 
 pub use crate::ffi::SessionOpenMode;
 pub use crate::ffi::QofBackendError;
@@ -27,14 +32,65 @@ pub fn is_engine_initialized() -> bool {
     unsafe { ffi::gnc_engine_is_initialized() != 0 }
 }
 
+thread_local! {
+    /// The progress closure of the session call running on this thread, as a
+    /// pointer to a `&mut dyn FnMut(&str, f64)`, or null.
+    static PROGRESS: Cell<*mut c_void> = const { Cell::new(std::ptr::null_mut()) };
+    /// A panic raised by the progress closure, resumed once the engine returns.
+    static PROGRESS_PANIC: RefCell<Option<Box<dyn Any + Send>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `progress` receiving the engine's progress reports.
+///
+/// `QofPercentageFunc` has no user data argument, so the closure is reached
+/// through a thread-local that [`progress_trampoline`] reads. The backend
+/// keeps the trampoline after a load or save, which is how
+/// `qof_session_ensure_all_data_loaded` reports progress too.
+fn with_progress<R>(mut progress: &mut dyn FnMut(&str, f64), f: impl FnOnce() -> R) -> R {
+    let ptr = (&mut progress as *mut &mut dyn FnMut(&str, f64)).cast::<c_void>();
+    let previous = PROGRESS.replace(ptr);
+    let result = f();
+    PROGRESS.set(previous);
+    if let Some(payload) = PROGRESS_PANIC.take() {
+        std::panic::resume_unwind(payload);
+    }
+    result
+}
+
+/// The `QofPercentageFunc` handed to the engine; forwards to the closure
+/// installed by [`with_progress`], if any.
+unsafe extern "C" fn progress_trampoline(message: *const c_char, percent: f64) {
+    let ptr = PROGRESS.get();
+    if ptr.is_null() || PROGRESS_PANIC.with_borrow(Option::is_some) {
+        return;
+    }
+    let message = if message.is_null() {
+        "".into()
+    } else {
+        unsafe { CStr::from_ptr(message) }.to_string_lossy()
+    };
+    let progress = unsafe { &mut *ptr.cast::<&mut dyn FnMut(&str, f64)>() };
+    // Unwinding out of an extern "C" fn aborts, so carry the panic across
+    if let Err(payload) = std::panic::catch_unwind(AssertUnwindSafe(|| progress(&message, percent)))
+    {
+        PROGRESS_PANIC.set(Some(payload));
+    }
+}
+
 /// A GnuCash Session - connection to a data store (file or database).
 ///
 /// A Session encapsulates a connection to a GnuCash data file or database.
 /// It manages file locking, loading, and saving of data.
+///
+/// The session owns its book. [`Session::book`] lends it out, so accounts,
+/// transactions and other entities obtained from it cannot outlive the session.
 pub struct Session {
     ptr: NonNull<ffi::QofSession>,
+    book: Book,
 }
 
+/// Kept for compatibility; the engine is not thread-safe. Prefer
+/// `EngineHandle` (`async` feature); see [Threads](crate#threads).
 unsafe impl Send for Session {}
 
 impl Session {
@@ -43,8 +99,18 @@ impl Session {
         init_engine();
         let book = unsafe { ffi::qof_book_new() };
         let ptr = unsafe { ffi::qof_session_new(book) };
+        unsafe { Self::from_parts(ptr, book) }
+    }
+
+    /// Wraps a new session and the book it was created with.
+    ///
+    /// # Safety
+    /// `book` must be the book of the session `ptr`, which owns it.
+    unsafe fn from_parts(ptr: *mut ffi::QofSession, book: *mut ffi::QofBook) -> Self {
         Self {
             ptr: NonNull::new(ptr).expect("qof_session_new returned null"),
+            book: unsafe { Book::from_raw(book, false) }
+                .expect("qof_book_new returned null"),
         }
     }
 
@@ -54,64 +120,94 @@ impl Session {
     /// * `uri` - Path to the file (e.g., "file:///path/to/file.gnucash" or just "/path/to/file.gnucash")
     /// * `mode` - How to open the file (read-only, create new, etc.)
     ///
-    /// # Returns
-    /// `Ok(Session)` on success, `Err(QofBackendError)` on failure.
-    pub fn open(uri: &str, mode: SessionOpenMode) -> Result<Self, QofBackendError> {
+    /// See [`SessionOptions`] for lock handling beyond the plain modes.
+    ///
+    /// # Errors
+    /// Returns [`Error::Backend`] with the engine's code and message if the
+    /// data store cannot be opened or loaded; see [`Error::is_locked`] and
+    /// friends. A `uri` containing a null byte gives
+    /// [`Error::StringConversion`].
+    pub fn open(uri: &str, mode: SessionOpenMode) -> Result<Self> {
+        Self::open_with_progress(uri, mode, |_, _| {})
+    }
+
+    /// Opens a GnuCash data file or database, reporting load progress.
+    ///
+    /// `progress` is called with a status message (often empty) and the
+    /// percentage done, from 0 to 100, as the backend loads the book. A
+    /// negative percentage marks the end of the load.
+    ///
+    /// ```ignore
+    /// let session = Session::open_with_progress(path, mode, |msg, pct| {
+    ///     eprint!("\rLoading {:5.1}% {}", pct, msg);
+    /// })?;
+    /// ```
+    ///
+    /// # Errors
+    /// As for [`Session::open`].
+    pub fn open_with_progress(
+        uri: &str,
+        mode: SessionOpenMode,
+        mut progress: impl FnMut(&str, f64),
+    ) -> Result<Self> {
+        let session = Self::begin(uri, mode)?;
+
+        // Load the data
+        with_progress(&mut progress, || unsafe {
+            ffi::qof_session_load(session.ptr.as_ptr(), Some(progress_trampoline));
+        });
+        session.check_error()?;
+
+        Ok(session)
+    }
+
+    /// Creates a session with a new empty book and begins it on `uri`,
+    /// without loading any data.
+    fn begin(uri: &str, mode: SessionOpenMode) -> Result<Self> {
         init_engine();
 
         let book = unsafe { ffi::qof_book_new() };
         let ptr = unsafe { ffi::qof_session_new(book) };
-        let session = Self {
-            ptr: NonNull::new(ptr).expect("qof_session_new returned null"),
-        };
+        let session = unsafe { Self::from_parts(ptr, book) };
 
-        // Normalize the URI - add file:// prefix if it's a plain path
-        let uri = if uri.contains("://") {
-            uri.to_string()
-        } else {
-            format!("file://{}", uri)
-        };
-
-        let c_uri = CString::new(uri).map_err(|_| QofBackendError::ERR_BACKEND_BAD_URL)?;
+        let c_uri = CString::new(normalize_uri(uri))?;
 
         unsafe {
             ffi::qof_session_begin(session.ptr.as_ptr(), c_uri.as_ptr(), mode);
         }
-
-        let err = session.get_error();
-        if err != QofBackendError::ERR_BACKEND_NO_ERR {
-            return Err(err);
-        }
-
-        // Load the data
-        unsafe {
-            ffi::qof_session_load(session.ptr.as_ptr(), None);
-        }
-
-        let err = session.get_error();
-        if err != QofBackendError::ERR_BACKEND_NO_ERR {
-            return Err(err);
-        }
-
+        session.check_error()?;
         Ok(session)
     }
 
     /// Opens a file read-only (convenience method).
-    pub fn open_readonly(path: &str) -> Result<Self, QofBackendError> {
+    pub fn open_readonly(path: &str) -> Result<Self> {
         Self::open(path, SessionOpenMode::SESSION_READ_ONLY)
     }
 
+    /// Inspects the lock on the data store at `uri` without opening it.
+    ///
+    /// Reports who holds the lock and for how long, as far as the backend
+    /// records it; see [`LockInfo`](crate::LockInfo). XML files are always
+    /// inspected; SQLite files need the `sqlite` feature and give
+    /// [`LockStatus::Unknown`] without it, as do server databases.
+    ///
+    /// # Errors
+    /// Returns [`Error::Other`] if the lock exists but cannot be read.
+    pub fn lock_status(uri: &str) -> Result<LockStatus> {
+        lock::lock_status(uri)
+    }
+
     /// Returns the raw pointer to the underlying QofSession.
     pub fn as_ptr(&self) -> *mut ffi::QofSession {
         self.ptr.as_ptr()
     }
 
     /// Returns the book associated with this session.
-    pub fn book(&self) -> Option<Book> {
-        unsafe {
-            let ptr = ffi::qof_session_get_book(self.ptr.as_ptr());
-            Book::from_raw(ptr, false)
-        }
+    ///
+    /// The book is borrowed from the session, so it and every entity taken
+    /// from it must be dropped before the session is.
+    pub fn book(&self) -> Option<&Book> {
+        Some(&self.book)
     }
 
     /// Returns the last error from the session.
@@ -136,6 +232,23 @@ impl Session {
         }
     }
 
+    /// Returns the pending session error, if any, as an [`Error::Backend`].
+    ///
+    /// The message comes from `qof_session_get_error_message`, falling back
+    /// to a description of the code. The error is cleared from the session.
+    pub fn check_error(&self) -> Result<()> {
+        let code = self.get_error();
+        if code == QofBackendError::ERR_BACKEND_NO_ERR {
+            return Ok(());
+        }
+        let message = self
+            .get_error_message()
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| crate::error::backend_error_description(code));
+        self.pop_error();
+        Err(Error::Backend { code, message })
+    }
+
     /// Returns the file path for this session.
     pub fn file_path(&self) -> Option<String> {
         unsafe {
@@ -161,16 +274,72 @@ impl Session {
     }
 
     /// Saves the session data.
-    pub fn save(&self) -> Result<(), QofBackendError> {
+    ///
+    /// # Errors
+    /// Returns [`Error::Backend`] if the backend fails to write the data.
+    pub fn save(&self) -> Result<()> {
+        self.save_with_progress(|_, _| {})
+    }
+
+    /// Saves the session data, reporting progress.
+    ///
+    /// `progress` is called as for [`Session::open_with_progress`].
+    ///
+    /// # Errors
+    /// Returns [`Error::Backend`] if the backend fails to write the data.
+    pub fn save_with_progress(&self, mut progress: impl FnMut(&str, f64)) -> Result<()> {
+        with_progress(&mut progress, || unsafe {
+            ffi::qof_session_save(self.ptr.as_ptr(), Some(progress_trampoline));
+        });
+        self.check_error()
+    }
+
+    /// Saves the session data to a new data store and switches to it.
+    ///
+    /// `uri` may use a different backend from the current one, so this
+    /// converts between formats, e.g. from `file://` XML to `sqlite3://`.
+    /// Any existing store at `uri` is replaced. The whole book moves to the
+    /// new store with every entity intact, as GnuCash's "Save As" does.
+    ///
+    /// On success the session refers to the new store and the lock on the
+    /// old one is released. On failure the session is left on the old store
+    /// with its data.
+    ///
+    /// # Errors
+    /// Returns [`Error::ReadOnly`] if the book is read-only (such as a
+    /// session opened with [`SessionOpenMode::SESSION_READ_ONLY`]), since
+    /// the engine cannot save it, and [`Error::Backend`] if the new store
+    /// cannot be created or written.
+    pub fn save_as(&mut self, uri: &str) -> Result<()> {
+        if self.book.is_readonly() {
+            return Err(Error::ReadOnly);
+        }
+        self.ensure_all_data_loaded();
+
+        let mut target = Self::begin(uri, SessionOpenMode::SESSION_NEW_OVERWRITE)?;
+        self.swap_data(&mut target);
         unsafe {
-            ffi::qof_session_save(self.ptr.as_ptr(), None);
+            ffi::qof_book_mark_session_dirty(target.book.as_ptr());
+            ffi::qof_session_safe_save(target.ptr.as_ptr(), Some(progress_trampoline));
         }
-        let err = self.get_error();
-        if err != QofBackendError::ERR_BACKEND_NO_ERR {
-            Err(err)
-        } else {
-            Ok(())
+        if let Err(e) = target.check_error() {
+            self.swap_data(&mut target);
+            return Err(e);
+        }
+
+        std::mem::swap(self, &mut target);
+        Ok(())
+    }
+
+    /// Exchanges the books of two sessions, in the engine and in the
+    /// wrappers.
+    fn swap_data(&mut self, other: &mut Session) {
+        unsafe {
+            ffi::qof_event_suspend();
+            ffi::qof_session_swap_data(self.ptr.as_ptr(), other.ptr.as_ptr());
+            ffi::qof_event_resume();
         }
+        std::mem::swap(&mut self.book, &mut other.book);
     }
 
     /// Ensures all data is loaded from the session.
@@ -180,6 +349,16 @@ impl Session {
         }
     }
 
+    /// Ensures all data is loaded from the session, reporting progress.
+    ///
+    /// `progress` is called as for [`Session::open_with_progress`], for
+    /// backends that load lazily and report progress (the SQL backends).
+    pub fn ensure_all_data_loaded_with_progress(&self, mut progress: impl FnMut(&str, f64)) {
+        with_progress(&mut progress, || unsafe {
+            ffi::qof_session_ensure_all_data_loaded(self.ptr.as_ptr());
+        });
+    }
+
     /// Ends the session (releases locks, etc.).
     pub fn end(&self) {
         unsafe {
@@ -188,6 +367,132 @@ impl Session {
     }
 }
 
+/// Adds a `file://` prefix to a plain path.
+fn normalize_uri(uri: &str) -> String {
+    if uri.contains("://") {
+        uri.to_string()
+    } else {
+        format!("file://{}", uri)
+    }
+}
+
+/// Options for opening a [`Session`], including how to treat a locked store.
+///
+/// Works like [`std::fs::OpenOptions`]: pick the options, then call
+/// [`open`](Self::open).
+///
+/// ```ignore
+/// use gnucash_sys::SessionOptions;
+///
+/// // Open for writing, or read-only if another session has the file
+/// let session = SessionOptions::new()
+///     .read_only_if_locked(true)
+///     .open("/path/to/file.gnucash")?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionOptions {
+    mode: SessionOpenMode,
+    read_only_if_locked: bool,
+}
+
+impl SessionOptions {
+    /// Options for opening an existing, unlocked data store read-write.
+    pub fn new() -> Self {
+        Self {
+            mode: SessionOpenMode::SESSION_NORMAL_OPEN,
+            read_only_if_locked: false,
+        }
+    }
+
+    /// Creates a new data store, failing if one already exists at the URI.
+    pub fn create(mut self) -> Self {
+        self.mode = SessionOpenMode::SESSION_NEW_STORE;
+        self
+    }
+
+    /// Creates a new data store, replacing any that exists at the URI.
+    pub fn create_overwrite(mut self) -> Self {
+        self.mode = SessionOpenMode::SESSION_NEW_OVERWRITE;
+        self
+    }
+
+    /// Opens the data store read-only.
+    ///
+    /// No lock is taken, and an existing lock is left alone. The book is
+    /// marked read-only, so setters return [`Error::ReadOnly`] and nothing
+    /// can be saved.
+    pub fn read_only(mut self) -> Self {
+        self.mode = SessionOpenMode::SESSION_READ_ONLY;
+        self
+    }
+
+    /// Opens the data store even if another session has locked it.
+    ///
+    /// The engine only allows this read-only, so it is the same mode as
+    /// [`read_only`](Self::read_only); it corresponds to the engine's older
+    /// `ignore_lock` flag. Use [`break_lock`](Self::break_lock) to write.
+    pub fn ignore_lock(self) -> Self {
+        self.read_only()
+    }
+
+    /// Opens the data store read-write, replacing any existing lock.
+    ///
+    /// Only do this when the holder is known to be gone, e.g. after a crash;
+    /// [`Session::lock_status`] shows who holds the lock and since when.
+    pub fn break_lock(mut self) -> Self {
+        self.mode = SessionOpenMode::SESSION_BREAK_LOCK;
+        self
+    }
+
+    /// Falls back to opening read-only if the data store is locked.
+    ///
+    /// Applies to the read-write modes. Check
+    /// [`Book::is_readonly`] on the session's book to see which was used.
+    pub fn read_only_if_locked(mut self, fallback: bool) -> Self {
+        self.read_only_if_locked = fallback;
+        self
+    }
+
+    /// Returns the engine open mode these options use.
+    pub fn mode(&self) -> SessionOpenMode {
+        self.mode
+    }
+
+    /// Opens the data store at `uri` with these options.
+    ///
+    /// # Errors
+    /// As for [`Session::open`]. With
+    /// [`read_only_if_locked`](Self::read_only_if_locked), a locked store is
+    /// reopened read-only instead of returning a locked error.
+    pub fn open(&self, uri: &str) -> Result<Session> {
+        self.open_with_progress(uri, |_, _| {})
+    }
+
+    /// Opens the data store at `uri` with these options, reporting load
+    /// progress as [`Session::open_with_progress`] does.
+    ///
+    /// # Errors
+    /// As for [`open`](Self::open).
+    pub fn open_with_progress(
+        &self,
+        uri: &str,
+        mut progress: impl FnMut(&str, f64),
+    ) -> Result<Session> {
+        match Session::open_with_progress(uri, self.mode, &mut progress) {
+            Err(e) if e.is_locked() && self.read_only_if_locked => {
+                Session::open_with_progress(uri, SessionOpenMode::SESSION_READ_ONLY, progress)
+            }
+            result => result,
+        }
+    }
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Default for Session {
     fn default() -> Self {
         Self::new()
@@ -211,3 +516,96 @@ impl std::fmt::Debug for Session {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, Editable};
+
+    #[test]
+    fn test_session_options_modes() {
+        assert_eq!(SessionOptions::new().mode(), SessionOpenMode::SESSION_NORMAL_OPEN);
+        assert_eq!(SessionOptions::new().create().mode(), SessionOpenMode::SESSION_NEW_STORE);
+        assert_eq!(
+            SessionOptions::new().create_overwrite().mode(),
+            SessionOpenMode::SESSION_NEW_OVERWRITE
+        );
+        assert_eq!(SessionOptions::new().read_only().mode(), SessionOpenMode::SESSION_READ_ONLY);
+        assert_eq!(SessionOptions::new().ignore_lock().mode(), SessionOpenMode::SESSION_READ_ONLY);
+        assert_eq!(SessionOptions::new().break_lock().mode(), SessionOpenMode::SESSION_BREAK_LOCK);
+    }
+
+    #[test]
+    fn test_normalize_uri() {
+        assert_eq!(normalize_uri("/tmp/books.gnucash"), "file:///tmp/books.gnucash");
+        assert_eq!(normalize_uri("sqlite3:///tmp/books.gnucash"), "sqlite3:///tmp/books.gnucash");
+    }
+
+    #[test]
+    fn test_open_missing_file_is_not_locked() {
+        let err = SessionOptions::new()
+            .read_only_if_locked(true)
+            .open("xml:///nonexistent/books.gnucash")
+            .unwrap_err();
+        assert!(!err.is_locked());
+    }
+
+    #[test]
+    fn test_save_as_moves_book_to_new_store() {
+        // Saving and loading suspend engine events
+        let _lock = crate::event::lock_engine_events();
+        let dir = std::env::temp_dir().join(format!("gnucash-save-as-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = format!("xml://{}", dir.join("books.gnucash").display());
+        let copy = format!("xml://{}", dir.join("copy.gnucash").display());
+
+        let mut session = Session::open(&original, SessionOpenMode::SESSION_NEW_STORE).unwrap();
+        {
+            let book = session.book().unwrap();
+            let root = book.root_account().unwrap();
+            let mut checking = Account::new(book);
+            checking.edit_with(|a| a.set_name("Checking")).unwrap();
+            root.append_child(&checking);
+            checking.mark_unowned();
+        }
+
+        session.save_as(&copy).unwrap();
+        assert!(session.url().unwrap().ends_with("copy.gnucash"));
+        drop(session);
+
+        let reopened = Session::open_readonly(&copy).unwrap();
+        let root = reopened.book().unwrap().root_account().unwrap();
+        assert!(root.lookup_by_name("Checking").is_some());
+        drop(root);
+        drop(reopened);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_as_rejects_readonly_book() {
+        let mut session = Session::new();
+        session.book().unwrap().mark_readonly();
+        assert_eq!(session.save_as("xml:///tmp/unused.gnucash"), Err(Error::ReadOnly));
+    }
+
+    #[test]
+    fn test_progress_trampoline_forwards_to_closure() {
+        let mut reports = Vec::new();
+        with_progress(&mut |msg: &str, pct| reports.push((msg.to_string(), pct)), || unsafe {
+            progress_trampoline(c"Loading".as_ptr(), 50.0);
+            progress_trampoline(std::ptr::null(), -1.0);
+        });
+        assert_eq!(reports, vec![("Loading".to_string(), 50.0), (String::new(), -1.0)]);
+
+        // Outside with_progress the trampoline does nothing
+        unsafe { progress_trampoline(std::ptr::null(), 10.0) };
+    }
+
+    #[test]
+    #[should_panic(expected = "progress failed")]
+    fn test_progress_panic_resumes_after_engine_call() {
+        with_progress(&mut |_, _| panic!("progress failed"), || unsafe {
+            progress_trampoline(std::ptr::null(), 1.0);
+        });
+    }
+}
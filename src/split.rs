@@ -1,10 +1,11 @@
 //! Safe wrapper for GnuCash Split.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use crate::ffi;
-use crate::{Account, Book, Guid, Numeric, Transaction};
+use crate::{edit, ffi};
+use crate::{Account, Book, BookRef, Guid, Numeric, Result, Time64, Transaction};
 
 /// Reconcile state constants.
 pub mod reconcile {
@@ -25,20 +26,24 @@ pub mod reconcile {
 /// A Split represents one side of a double-entry transaction, linking
 /// an amount to an account. Each Split has both an 'amount' (in the
 /// account's commodity) and a 'value' (in the transaction's currency).
-pub struct Split {
+pub struct Split<'book> {
     ptr: NonNull<ffi::Split>,
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Split {}
+/// Kept for compatibility; the engine is not thread-safe. See
+/// [Threads](crate#threads).
+unsafe impl Send for Split<'_> {}
 
-impl Split {
+impl<'book> Split<'book> {
     /// Creates a new Split in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::xaccMallocSplit(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("xaccMallocSplit returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -47,7 +52,7 @@ impl Split {
     /// # Safety
     /// The pointer must be valid and point to a properly initialized Split.
     pub unsafe fn from_raw(ptr: *mut ffi::Split, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer to the underlying Split.
@@ -55,6 +60,12 @@ impl Split {
         self.ptr.as_ptr()
     }
 
+    /// Marks this split as not owned by this wrapper.
+    /// Call this after adding the split to a transaction, which then owns it.
+    pub fn mark_unowned(&mut self) {
+        self.owned = false;
+    }
+
     /// Returns the GUID of this split.
     pub fn guid(&self) -> Guid {
         unsafe {
@@ -76,20 +87,34 @@ impl Split {
     // ==================== Account/Transaction Linkage ====================
 
     /// Returns the account this split belongs to.
-    pub fn account(&self) -> Option<Account> {
+    pub fn account(&self) -> Option<Account<'book>> {
         unsafe {
             let ptr = ffi::xaccSplitGetAccount(self.ptr.as_ptr());
             Account::from_raw(ptr, false)
         }
     }
 
+    /// Checks that this split can be modified.
+    ///
+    /// A split that belongs to a transaction can only be changed while the
+    /// transaction is open for editing and not read-only.
+    fn check_editable(&self) -> Result<()> {
+        unsafe { edit::check_book_writable(self.ptr.as_ptr().cast())? };
+        match self.transaction() {
+            Some(trans) => trans.check_editable(),
+            None => Ok(()),
+        }
+    }
+
     /// Sets the account for this split.
-    pub fn set_account(&self, account: &Account) {
+    pub fn set_account(&self, account: &Account) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccSplitSetAccount(self.ptr.as_ptr(), account.as_ptr()) }
+        Ok(())
     }
 
     /// Returns the parent transaction of this split.
-    pub fn transaction(&self) -> Option<Transaction> {
+    pub fn transaction(&self) -> Option<Transaction<'book>> {
         unsafe {
             let ptr = ffi::xaccSplitGetParent(self.ptr.as_ptr());
             Transaction::from_raw(ptr, false)
@@ -97,16 +122,18 @@ impl Split {
     }
 
     /// Sets the parent transaction for this split.
-    pub fn set_transaction(&self, trans: &Transaction) {
+    ///
+    /// Both the current and the new transaction must be open for editing.
+    pub fn set_transaction(&self, trans: &Transaction) -> Result<()> {
+        self.check_editable()?;
+        trans.check_editable()?;
         unsafe { ffi::xaccSplitSetParent(self.ptr.as_ptr(), trans.as_ptr()) }
+        Ok(())
     }
 
     /// Returns the book this split belongs to.
-    pub fn book(&self) -> Option<Book> {
-        unsafe {
-            let ptr = ffi::xaccSplitGetBook(self.ptr.as_ptr());
-            Book::from_raw(ptr, false)
-        }
+    pub fn book(&self) -> Option<BookRef<'book>> {
+        unsafe { BookRef::from_raw(ffi::xaccSplitGetBook(self.ptr.as_ptr())) }
     }
 
     // ==================== Memo/Action ====================
@@ -124,13 +151,11 @@ impl Split {
     }
 
     /// Sets the split memo.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `memo` contains a null byte.
-    pub fn set_memo(&self, memo: &str) {
-        let c_memo = CString::new(memo).unwrap();
+    pub fn set_memo(&self, memo: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_memo = CString::new(memo)?;
         unsafe { ffi::xaccSplitSetMemo(self.ptr.as_ptr(), c_memo.as_ptr()) }
+        Ok(())
     }
 
     /// Returns the split action (e.g., "Buy", "Sell", "Deposit").
@@ -146,13 +171,11 @@ impl Split {
     }
 
     /// Sets the split action.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `action` contains a null byte.
-    pub fn set_action(&self, action: &str) {
-        let c_action = CString::new(action).unwrap();
+    pub fn set_action(&self, action: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_action = CString::new(action)?;
         unsafe { ffi::xaccSplitSetAction(self.ptr.as_ptr(), c_action.as_ptr()) }
+        Ok(())
     }
 
     /// Returns the split type ("normal" or "stock-split").
@@ -180,8 +203,10 @@ impl Split {
     }
 
     /// Sets the amount in the account's commodity.
-    pub fn set_amount(&self, amount: Numeric) {
+    pub fn set_amount(&self, amount: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccSplitSetAmount(self.ptr.as_ptr(), amount.into()) }
+        Ok(())
     }
 
     /// Returns the value in the transaction's currency.
@@ -190,8 +215,10 @@ impl Split {
     }
 
     /// Sets the value in the transaction's currency.
-    pub fn set_value(&self, value: Numeric) {
+    pub fn set_value(&self, value: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccSplitSetValue(self.ptr.as_ptr(), value.into()) }
+        Ok(())
     }
 
     /// Returns the share price (value / amount).
@@ -200,10 +227,12 @@ impl Split {
     }
 
     /// Sets both share price and amount simultaneously.
-    pub fn set_share_price_and_amount(&self, price: Numeric, amount: Numeric) {
+    pub fn set_share_price_and_amount(&self, price: Numeric, amount: Numeric) -> Result<()> {
+        self.check_editable()?;
         unsafe {
             ffi::xaccSplitSetSharePriceAndAmount(self.ptr.as_ptr(), price.into(), amount.into())
         }
+        Ok(())
     }
 
     // ==================== Balances ====================
@@ -236,18 +265,22 @@ impl Split {
     }
 
     /// Sets the reconcile state.
-    pub fn set_reconcile_state(&self, state: char) {
+    pub fn set_reconcile_state(&self, state: char) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccSplitSetReconcile(self.ptr.as_ptr(), state as u8) }
+        Ok(())
     }
 
     /// Returns the date when this split was reconciled.
-    pub fn date_reconciled(&self) -> i64 {
-        unsafe { ffi::xaccSplitGetDateReconciled(self.ptr.as_ptr()) }
+    pub fn date_reconciled(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::xaccSplitGetDateReconciled(self.ptr.as_ptr()) })
     }
 
     /// Sets the date when this split was reconciled.
-    pub fn set_date_reconciled(&self, time: i64) {
-        unsafe { ffi::xaccSplitSetDateReconciledSecs(self.ptr.as_ptr(), time) }
+    pub fn set_date_reconciled(&self, time: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::xaccSplitSetDateReconciledSecs(self.ptr.as_ptr(), time.secs()) }
+        Ok(())
     }
 
     /// Returns true if this split is reconciled.
@@ -264,7 +297,7 @@ impl Split {
 
     /// Returns the other split in a two-split transaction.
     /// Returns None if the transaction has more than two splits.
-    pub fn other_split(&self) -> Option<Split> {
+    pub fn other_split(&self) -> Option<Split<'book>> {
         unsafe {
             let ptr = ffi::xaccSplitGetOtherSplit(self.ptr.as_ptr());
             Self::from_raw(ptr, false)
@@ -334,8 +367,10 @@ impl Split {
     }
 
     /// Adds a peer split to this split's lot-split list.
-    pub fn add_peer(&self, other: &Split, timestamp: i64) {
-        unsafe { ffi::xaccSplitAddPeerSplit(self.ptr.as_ptr(), other.ptr.as_ptr(), timestamp) }
+    pub fn add_peer(&self, other: &Split, timestamp: Time64) {
+        unsafe {
+            ffi::xaccSplitAddPeerSplit(self.ptr.as_ptr(), other.ptr.as_ptr(), timestamp.secs())
+        }
     }
 
     /// Removes a peer split from this split's lot-split list.
@@ -344,7 +379,7 @@ impl Split {
     }
 }
 
-impl Drop for Split {
+impl Drop for Split<'_> {
     fn drop(&mut self) {
         if self.owned {
             unsafe {
@@ -354,7 +389,7 @@ impl Drop for Split {
     }
 }
 
-impl std::fmt::Debug for Split {
+impl std::fmt::Debug for Split<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Split")
             .field("guid", &self.guid())
@@ -366,15 +401,15 @@ impl std::fmt::Debug for Split {
     }
 }
 
-impl PartialEq for Split {
+impl PartialEq for Split<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.guid() == other.guid()
     }
 }
 
-impl Eq for Split {}
+impl Eq for Split<'_> {}
 
-impl std::hash::Hash for Split {
+impl std::hash::Hash for Split<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.guid().hash(state);
     }
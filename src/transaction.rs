@@ -1,11 +1,14 @@
 //! Safe wrapper for GnuCash Transaction.
 
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::ptr::NonNull;
 
-use crate::ffi;
+use crate::{edit, ffi};
 use crate::iter::TransactionSplits;
-use crate::{Account, Book, Guid, Numeric, Split};
+use crate::{
+    Account, Book, Commodity, Editable, Error, GncDate, Guid, Numeric, Result, Split, Time64,
+};
 
 /// Transaction type constants.
 pub mod txn_type {
@@ -19,20 +22,24 @@ pub mod txn_type {
 ///
 /// A Transaction contains one or more Splits that must balance to zero.
 /// Each transaction has a date, description, and currency.
-pub struct Transaction {
+pub struct Transaction<'book> {
     ptr: NonNull<ffi::Transaction>,
     owned: bool,
+    _book: PhantomData<&'book Book>,
 }
 
-unsafe impl Send for Transaction {}
+/// Kept for compatibility; the engine is not thread-safe. See
+/// [Threads](crate#threads).
+unsafe impl Send for Transaction<'_> {}
 
-impl Transaction {
+impl<'book> Transaction<'book> {
     /// Creates a new Transaction in the given book.
-    pub fn new(book: &Book) -> Self {
+    pub fn new(book: &'book Book) -> Self {
         let ptr = unsafe { ffi::xaccMallocTransaction(book.as_ptr()) };
         Self {
             ptr: NonNull::new(ptr).expect("xaccMallocTransaction returned null"),
             owned: true,
+            _book: PhantomData,
         }
     }
 
@@ -41,7 +48,7 @@ impl Transaction {
     /// # Safety
     /// The pointer must be valid and point to a properly initialized Transaction.
     pub unsafe fn from_raw(ptr: *mut ffi::Transaction, owned: bool) -> Option<Self> {
-        NonNull::new(ptr).map(|ptr| Self { ptr, owned })
+        NonNull::new(ptr).map(|ptr| Self { ptr, owned, _book: PhantomData })
     }
 
     /// Returns the raw pointer to the underlying Transaction.
@@ -49,6 +56,12 @@ impl Transaction {
         self.ptr.as_ptr()
     }
 
+    /// Marks this transaction as not owned by this wrapper.
+    /// Call this once the transaction is committed and should stay in the book.
+    pub fn mark_unowned(&mut self) {
+        self.owned = false;
+    }
+
     /// Returns the GUID of this transaction.
     pub fn guid(&self) -> Guid {
         unsafe {
@@ -179,113 +192,156 @@ impl Transaction {
         unsafe { ffi::xaccTransIsReadonlyByPostedDate(self.ptr.as_ptr()) != 0 }
     }
 
+    /// Returns the currency in which this transaction's values are denominated.
+    pub fn currency(&self) -> Option<Commodity<'book>> {
+        unsafe { Commodity::from_raw(ffi::xaccTransGetCurrency(self.ptr.as_ptr()), false) }
+    }
+
     // ==================== Setters ====================
 
-    /// Sets the transaction description.
+    /// Checks that this transaction can be modified.
     ///
-    /// # Panics
-    ///
-    /// Panics if `desc` contains a null byte.
-    pub fn set_description(&self, desc: &str) {
-        let c_desc = CString::new(desc).unwrap();
+    /// Besides requiring a writable book and an open edit, a transaction
+    /// marked read-only, or one posted before the book's auto-read-only
+    /// threshold, cannot be changed.
+    pub(crate) fn check_editable(&self) -> Result<()> {
+        self.check_open()?;
+        if self.read_only_reason().is_some()
+            || (self.date_posted().secs() != 0 && self.is_readonly_by_posted_date())
+        {
+            return Err(Error::ReadOnly);
+        }
+        Ok(())
+    }
+
+    /// Checks that the book is writable and the transaction is open for editing.
+    fn check_open(&self) -> Result<()> {
+        unsafe { edit::check_editable(self.ptr.as_ptr().cast(), "transaction") }
+    }
+
+    /// Sets the transaction description.
+    pub fn set_description(&self, desc: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_desc = CString::new(desc)?;
         unsafe { ffi::xaccTransSetDescription(self.ptr.as_ptr(), c_desc.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the transaction number (ID).
-    ///
-    /// # Panics
-    ///
-    /// Panics if `num` contains a null byte.
-    pub fn set_num(&self, num: &str) {
-        let c_num = CString::new(num).unwrap();
+    pub fn set_num(&self, num: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_num = CString::new(num)?;
         unsafe { ffi::xaccTransSetNum(self.ptr.as_ptr(), c_num.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the transaction notes.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `notes` contains a null byte.
-    pub fn set_notes(&self, notes: &str) {
-        let c_notes = CString::new(notes).unwrap();
+    pub fn set_notes(&self, notes: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_notes = CString::new(notes)?;
         unsafe { ffi::xaccTransSetNotes(self.ptr.as_ptr(), c_notes.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the document link URL.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `link` contains a null byte.
-    pub fn set_doc_link(&self, link: &str) {
-        let c_link = CString::new(link).unwrap();
+    pub fn set_doc_link(&self, link: &str) -> Result<()> {
+        self.check_editable()?;
+        let c_link = CString::new(link)?;
         unsafe { ffi::xaccTransSetDocLink(self.ptr.as_ptr(), c_link.as_ptr()) }
+        Ok(())
+    }
+
+    /// Sets the transaction currency.
+    pub fn set_currency(&self, currency: &Commodity) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::xaccTransSetCurrency(self.ptr.as_ptr(), currency.as_ptr()) }
+        Ok(())
     }
 
     /// Sets the transaction type.
-    pub fn set_txn_type(&self, txn_type: char) {
+    pub fn set_txn_type(&self, txn_type: char) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccTransSetTxnType(self.ptr.as_ptr(), txn_type as u8) }
+        Ok(())
     }
 
     /// Sets whether this is a closing transaction.
-    pub fn set_is_closing(&self, is_closing: bool) {
+    pub fn set_is_closing(&self, is_closing: bool) -> Result<()> {
+        self.check_editable()?;
         unsafe { ffi::xaccTransSetIsClosingTxn(self.ptr.as_ptr(), is_closing as i32) }
+        Ok(())
     }
 
     /// Sets the read-only flag with a reason.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `reason` contains a null byte.
-    pub fn set_read_only(&self, reason: &str) {
-        let c_reason = CString::new(reason).unwrap();
+    pub fn set_read_only(&self, reason: &str) -> Result<()> {
+        self.check_open()?;
+        let c_reason = CString::new(reason)?;
         unsafe { ffi::xaccTransSetReadOnly(self.ptr.as_ptr(), c_reason.as_ptr()) }
+        Ok(())
     }
 
     /// Clears the read-only flag.
-    pub fn clear_read_only(&self) {
+    pub fn clear_read_only(&self) -> Result<()> {
+        self.check_open()?;
         unsafe { ffi::xaccTransClearReadOnly(self.ptr.as_ptr()) }
+        Ok(())
     }
 
     // ==================== Dates ====================
 
-    /// Returns the posted date as a Unix timestamp.
-    pub fn date_posted(&self) -> i64 {
-        unsafe { ffi::xaccTransRetDatePosted(self.ptr.as_ptr()) }
+    /// Returns the posted date.
+    pub fn date_posted(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::xaccTransRetDatePosted(self.ptr.as_ptr()) })
     }
 
-    /// Returns the entered date as a Unix timestamp.
-    pub fn date_entered(&self) -> i64 {
-        unsafe { ffi::xaccTransRetDateEntered(self.ptr.as_ptr()) }
+    /// Returns the entered date.
+    pub fn date_entered(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::xaccTransRetDateEntered(self.ptr.as_ptr()) })
     }
 
-    /// Returns the due date as a Unix timestamp (for invoices).
-    pub fn date_due(&self) -> i64 {
-        unsafe { ffi::xaccTransRetDateDue(self.ptr.as_ptr()) }
+    /// Returns the due date (for invoices).
+    pub fn date_due(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::xaccTransRetDateDue(self.ptr.as_ptr()) })
     }
 
-    /// Returns the void time as a Unix timestamp.
-    pub fn void_time(&self) -> i64 {
-        unsafe { ffi::xaccTransGetVoidTime(self.ptr.as_ptr()) }
+    /// Returns the void time.
+    pub fn void_time(&self) -> Time64 {
+        Time64::from_secs(unsafe { ffi::xaccTransGetVoidTime(self.ptr.as_ptr()) })
     }
 
-    /// Sets the posted date using day, month, year.
-    pub fn set_date(&self, day: i32, month: i32, year: i32) {
-        unsafe { ffi::xaccTransSetDate(self.ptr.as_ptr(), day, month, year) }
+    /// Sets the posted date to the given calendar day.
+    pub fn set_date(&self, date: GncDate) -> Result<()> {
+        self.check_editable()?;
+        unsafe {
+            ffi::xaccTransSetDate(
+                self.ptr.as_ptr(),
+                date.day() as i32,
+                date.month() as i32,
+                date.year(),
+            )
+        }
+        Ok(())
     }
 
-    /// Sets the posted date as a Unix timestamp (normalized to date only).
-    pub fn set_date_posted(&self, time: i64) {
-        unsafe { ffi::xaccTransSetDatePostedSecsNormalized(self.ptr.as_ptr(), time) }
+    /// Sets the posted date (normalized to date only).
+    pub fn set_date_posted(&self, time: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::xaccTransSetDatePostedSecsNormalized(self.ptr.as_ptr(), time.secs()) }
+        Ok(())
     }
 
-    /// Sets the entered date as a Unix timestamp.
-    pub fn set_date_entered(&self, time: i64) {
-        unsafe { ffi::xaccTransSetDateEnteredSecs(self.ptr.as_ptr(), time) }
+    /// Sets the entered date.
+    pub fn set_date_entered(&self, time: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::xaccTransSetDateEnteredSecs(self.ptr.as_ptr(), time.secs()) }
+        Ok(())
     }
 
-    /// Sets the due date as a Unix timestamp.
-    pub fn set_date_due(&self, time: i64) {
-        unsafe { ffi::xaccTransSetDateDue(self.ptr.as_ptr(), time) }
+    /// Sets the due date.
+    pub fn set_date_due(&self, time: Time64) -> Result<()> {
+        self.check_editable()?;
+        unsafe { ffi::xaccTransSetDateDue(self.ptr.as_ptr(), time.secs()) }
+        Ok(())
     }
 
     // ==================== Splits ====================
@@ -349,12 +405,11 @@ impl Transaction {
 
     /// Voids the transaction with a reason.
     ///
-    /// # Panics
-    ///
-    /// Panics if `reason` contains a null byte.
-    pub fn void(&self, reason: &str) {
-        let c_reason = CString::new(reason).unwrap();
+    /// Returns [`Error::StringConversion`] if `reason` contains a null byte.
+    pub fn void(&self, reason: &str) -> Result<()> {
+        let c_reason = CString::new(reason)?;
         unsafe { ffi::xaccTransVoid(self.ptr.as_ptr(), c_reason.as_ptr()) }
+        Ok(())
     }
 
     /// Unvoids a voided transaction.
@@ -363,7 +418,7 @@ impl Transaction {
     }
 
     /// Creates a reverse transaction that cancels this one.
-    pub fn reverse(&self) -> Option<Transaction> {
+    pub fn reverse(&self) -> Option<Transaction<'book>> {
         unsafe {
             let ptr = ffi::xaccTransReverse(self.ptr.as_ptr());
             Self::from_raw(ptr, true)
@@ -371,7 +426,7 @@ impl Transaction {
     }
 
     /// Returns the transaction that reversed this one, if any.
-    pub fn reversed_by(&self) -> Option<Transaction> {
+    pub fn reversed_by(&self) -> Option<Transaction<'book>> {
         unsafe {
             let ptr = ffi::xaccTransGetReversedBy(self.ptr.as_ptr());
             Self::from_raw(ptr, false)
@@ -393,12 +448,28 @@ impl Transaction {
     // ==================== Iterators ====================
 
     /// Returns an iterator over the splits in this transaction.
-    pub fn splits(&self) -> TransactionSplits {
+    pub fn splits(&self) -> TransactionSplits<'book> {
         TransactionSplits::new(self)
     }
 }
 
-impl Drop for Transaction {
+impl Editable for Transaction<'_> {
+    const SUPPORTS_ROLLBACK: bool = true;
+
+    fn begin_edit(&self) {
+        Transaction::begin_edit(self)
+    }
+
+    fn commit_edit(&self) {
+        Transaction::commit_edit(self)
+    }
+
+    fn end_edit(&self) {
+        Transaction::rollback_edit(self)
+    }
+}
+
+impl Drop for Transaction<'_> {
     fn drop(&mut self) {
         if self.owned {
             unsafe {
@@ -413,7 +484,7 @@ impl Drop for Transaction {
     }
 }
 
-impl std::fmt::Debug for Transaction {
+impl std::fmt::Debug for Transaction<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Transaction")
             .field("guid", &self.guid())
@@ -425,15 +496,15 @@ impl std::fmt::Debug for Transaction {
     }
 }
 
-impl PartialEq for Transaction {
+impl PartialEq for Transaction<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.guid() == other.guid()
     }
 }
 
-impl Eq for Transaction {}
+impl Eq for Transaction<'_> {}
 
-impl std::hash::Hash for Transaction {
+impl std::hash::Hash for Transaction<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.guid().hash(state);
     }
@@ -1,11 +1,11 @@
 //! Safe Rust wrappers for GnuCash core types.
 
+use std::ffi::{CStr, CString};
 use std::fmt;
+use std::str::FromStr;
 
 use crate::ffi;
-
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use crate::{Error, Result};
 
 /// GUID encoding length (32 hex characters).
 pub const GUID_ENCODING_LENGTH: usize = 32;
@@ -114,6 +114,38 @@ impl From<Guid> for ffi::GncGUID {
     }
 }
 
+/// Let the engine pick the denominator of an arithmetic result.
+///
+/// Passed as the `denom` argument of [`Numeric::checked_add`] and friends,
+/// this keeps the exact result instead of converting to a fixed denominator.
+pub const DENOM_AUTO: i64 = 0;
+
+// Denominator handling flags from gnc-numeric.h (not exported by bindgen).
+const GNC_HOW_DENOM_EXACT: i32 = 0x10;
+
+/// Rounding policy for [`Numeric`] arithmetic and conversions.
+///
+/// Mirrors the `GNC_HOW_RND_*` flags of the GnuCash engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round toward negative infinity.
+    Floor = 0x01,
+    /// Round toward positive infinity.
+    Ceil = 0x02,
+    /// Truncate fractions (round toward zero).
+    Trunc = 0x03,
+    /// Promote fractions (round away from zero).
+    Promote = 0x04,
+    /// Round to nearest, ties toward zero.
+    RoundHalfDown = 0x05,
+    /// Round to nearest, ties away from zero.
+    RoundHalfUp = 0x06,
+    /// Banker's rounding (round to nearest, ties to even).
+    Round = 0x07,
+    /// Never round; fail with a remainder error instead.
+    Never = 0x08,
+}
+
 /// A rational number with 64-bit numerator and denominator.
 ///
 /// Used for precise financial calculations without floating-point rounding errors.
@@ -135,6 +167,10 @@ impl From<Guid> for ffi::GncGUID {
 /// // Negate a value
 /// let neg = -amount;
 /// assert!(neg.is_negative());
+///
+/// // Exact rational arithmetic
+/// let total = amount + Numeric::new(250, 100);
+/// assert_eq!(total.to_f64(), 102.5);
 /// ```
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -200,6 +236,241 @@ impl Numeric {
     pub fn abs(&self) -> Self {
         Self::new(self.0.num.abs(), self.0.denom.abs())
     }
+
+    /// Returns true if this value is an engine error signal rather than a number.
+    pub fn is_error(&self) -> bool {
+        unsafe { ffi::gnc_numeric_check(self.0) != ffi::GNCNumericErrorCode::GNC_ERROR_OK }
+    }
+
+    /// Converts an engine error signal into `Error::Numeric`.
+    pub(crate) fn check(self) -> Result<Self> {
+        let code = unsafe { ffi::gnc_numeric_check(self.0) };
+        if code == ffi::GNCNumericErrorCode::GNC_ERROR_OK {
+            return Ok(self);
+        }
+        let msg = unsafe {
+            let ptr = ffi::gnc_numeric_errorCode_to_string(code);
+            if ptr.is_null() {
+                format!("{:?}", code)
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        };
+        Err(Error::Numeric(msg))
+    }
+
+    /// Builds the `how` flags for an arithmetic call.
+    fn how(denom: i64, mode: RoundingMode) -> i32 {
+        if denom == DENOM_AUTO {
+            GNC_HOW_DENOM_EXACT | mode as i32
+        } else {
+            mode as i32
+        }
+    }
+
+    // ==================== Arithmetic ====================
+
+    /// Adds two values, converting the result to `denom` using `mode`.
+    ///
+    /// Pass [`DENOM_AUTO`] to keep the exact result.
+    pub fn checked_add(&self, other: Numeric, denom: i64, mode: RoundingMode) -> Result<Numeric> {
+        let how = Self::how(denom, mode);
+        Numeric::from(unsafe { ffi::gnc_numeric_add(self.0, other.0, denom, how) }).check()
+    }
+
+    /// Subtracts `other`, converting the result to `denom` using `mode`.
+    ///
+    /// Pass [`DENOM_AUTO`] to keep the exact result.
+    pub fn checked_sub(&self, other: Numeric, denom: i64, mode: RoundingMode) -> Result<Numeric> {
+        let how = Self::how(denom, mode);
+        Numeric::from(unsafe { ffi::gnc_numeric_sub(self.0, other.0, denom, how) }).check()
+    }
+
+    /// Multiplies two values, converting the result to `denom` using `mode`.
+    ///
+    /// Pass [`DENOM_AUTO`] to keep the exact result.
+    pub fn checked_mul(&self, other: Numeric, denom: i64, mode: RoundingMode) -> Result<Numeric> {
+        let how = Self::how(denom, mode);
+        Numeric::from(unsafe { ffi::gnc_numeric_mul(self.0, other.0, denom, how) }).check()
+    }
+
+    /// Divides by `other`, converting the result to `denom` using `mode`.
+    ///
+    /// Pass [`DENOM_AUTO`] to keep the exact result. Division by zero
+    /// returns `Error::Numeric`.
+    pub fn checked_div(&self, other: Numeric, denom: i64, mode: RoundingMode) -> Result<Numeric> {
+        let how = Self::how(denom, mode);
+        Numeric::from(unsafe { ffi::gnc_numeric_div(self.0, other.0, denom, how) }).check()
+    }
+
+    /// Converts this value to a new denominator using `mode`.
+    pub fn convert(&self, denom: i64, mode: RoundingMode) -> Result<Numeric> {
+        Numeric::from(unsafe { ffi::gnc_numeric_convert(self.0, denom, mode as i32) }).check()
+    }
+
+    /// Reduces this value to lowest terms.
+    pub fn reduce(&self) -> Numeric {
+        unsafe { ffi::gnc_numeric_reduce(self.0).into() }
+    }
+
+    /// Returns true if both values represent the same number (e.g. 1/2 and 2/4).
+    pub fn equal(&self, other: Numeric) -> bool {
+        unsafe { ffi::gnc_numeric_equal(self.0, other.0) != 0 }
+    }
+
+    /// Compares the numeric values of two numbers.
+    pub fn compare(&self, other: Numeric) -> std::cmp::Ordering {
+        unsafe { ffi::gnc_numeric_compare(self.0, other.0) }.cmp(&0)
+    }
+
+    // ==================== Parsing/Formatting ====================
+
+    /// Parses a decimal string such as "1,234.56" or "-12.5".
+    ///
+    /// Commas are treated as thousands separators. Rational strings in the
+    /// `Display` form ("123456/100") are accepted as well.
+    pub fn parse_decimal(s: &str) -> Result<Numeric> {
+        let info = PrintInfo {
+            thousands_sep: Some(','),
+            ..PrintInfo::default()
+        };
+        Self::parse_with(s, &info)
+    }
+
+    /// Parses a decimal string using the separators of `info`.
+    pub fn parse_with(s: &str, info: &PrintInfo) -> Result<Numeric> {
+        let trimmed = s.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let mut normalized = String::with_capacity(digits.len() + 1);
+        if negative {
+            normalized.push('-');
+        }
+        for c in digits.chars() {
+            if Some(c) == info.thousands_sep {
+                continue;
+            } else if c == info.decimal_point {
+                normalized.push('.');
+            } else if c.is_ascii_digit() || c == '.' || c == '/' {
+                normalized.push(c);
+            } else {
+                return Err(Error::Numeric(format!("invalid number: {:?}", s)));
+            }
+        }
+        if !normalized.chars().any(|c| c.is_ascii_digit()) {
+            return Err(Error::Numeric(format!("invalid number: {:?}", s)));
+        }
+        let c_str = CString::new(normalized).map_err(|e| Error::StringConversion(e.to_string()))?;
+        Numeric::from(unsafe { ffi::gnc_numeric_from_string(c_str.as_ptr()) }).check()
+    }
+
+    /// Formats this value as a plain decimal string with enough places for
+    /// `fraction` (e.g. 100 gives "1234.56").
+    pub fn to_decimal_string(&self, fraction: i64) -> Result<String> {
+        self.format_with(&PrintInfo::new(fraction))
+    }
+
+    /// Formats this value according to `info`.
+    pub fn format_with(&self, info: &PrintInfo) -> Result<String> {
+        let places = decimal_places(info.fraction);
+        let denom = 10i64.pow(places);
+        let value = self.convert(denom, info.rounding)?;
+
+        let magnitude = value.num().unsigned_abs();
+        let int_part = (magnitude / denom as u64).to_string();
+        let frac_part = magnitude % denom as u64;
+
+        let mut out = String::new();
+        if value.is_negative() {
+            out.push('-');
+        } else if info.force_sign && !value.is_zero() {
+            out.push('+');
+        }
+        match info.thousands_sep {
+            Some(sep) => {
+                let head = match int_part.len() % 3 {
+                    0 => 3,
+                    n => n,
+                };
+                out.push_str(&int_part[..head]);
+                let mut pos = head;
+                while pos < int_part.len() {
+                    out.push(sep);
+                    out.push_str(&int_part[pos..pos + 3]);
+                    pos += 3;
+                }
+            }
+            None => out.push_str(&int_part),
+        }
+        if places > 0 {
+            out.push(info.decimal_point);
+            out.push_str(&format!("{:0width$}", frac_part, width = places as usize));
+        }
+        Ok(out)
+    }
+}
+
+/// Returns the number of decimal places needed to show `fraction`.
+fn decimal_places(fraction: i64) -> u32 {
+    let mut places = 0;
+    let mut denom: i64 = 1;
+    while denom < fraction && places < 18 {
+        denom *= 10;
+        places += 1;
+    }
+    places
+}
+
+/// Formatting options for [`Numeric::format_with`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use gnucash_sys::{Numeric, PrintInfo};
+///
+/// let info = PrintInfo {
+///     thousands_sep: Some(','),
+///     ..PrintInfo::new(100)
+/// };
+/// let n = Numeric::parse_decimal("-1234567.891").unwrap();
+/// assert_eq!(n.format_with(&info).unwrap(), "-1,234,567.89");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintInfo {
+    /// Smallest fraction to show, usually the commodity's fraction (100 for cents).
+    pub fraction: i64,
+    /// Character used as the decimal point.
+    pub decimal_point: char,
+    /// Character used to group thousands, if any.
+    pub thousands_sep: Option<char>,
+    /// Prefix positive values with '+'.
+    pub force_sign: bool,
+    /// Rounding used when converting to `fraction`.
+    pub rounding: RoundingMode,
+}
+
+impl PrintInfo {
+    /// Creates options for the given smallest fraction with no grouping.
+    pub fn new(fraction: i64) -> Self {
+        Self {
+            fraction,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for PrintInfo {
+    fn default() -> Self {
+        Self {
+            fraction: 100,
+            decimal_point: '.',
+            thousands_sep: None,
+            force_sign: false,
+            rounding: RoundingMode::RoundHalfUp,
+        }
+    }
 }
 
 impl Default for Numeric {
@@ -226,6 +497,14 @@ impl fmt::Display for Numeric {
     }
 }
 
+impl FromStr for Numeric {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Numeric::parse_decimal(s)
+    }
+}
+
 impl From<i64> for Numeric {
     fn from(n: i64) -> Self {
         Self::new(n, 1)
@@ -251,6 +530,29 @@ impl std::ops::Neg for Numeric {
     }
 }
 
+// The operators compute exact results and panic on engine errors
+// (overflow, division by zero), like integer arithmetic does.
+macro_rules! impl_numeric_op {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl std::ops::$trait for Numeric {
+            type Output = Self;
+            fn $method(self, rhs: Self) -> Self::Output {
+                match self.$checked(rhs, DENOM_AUTO, RoundingMode::Never) {
+                    Ok(n) => n,
+                    Err(e) => {
+                        panic!("{} of {} and {} failed: {}", stringify!($method), self, rhs, e)
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_numeric_op!(Add, add, checked_add);
+impl_numeric_op!(Sub, sub, checked_sub);
+impl_numeric_op!(Mul, mul, checked_mul);
+impl_numeric_op!(Div, div, checked_div);
+
 // ==================== Serde Support ====================
 
 #[cfg(feature = "serde")]
@@ -259,7 +561,7 @@ mod serde_impl {
     use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
     impl Serialize for Guid {
-        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
         where
             S: Serializer,
         {
@@ -268,7 +570,7 @@ mod serde_impl {
     }
 
     impl<'de> Deserialize<'de> for Guid {
-        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
         where
             D: Deserializer<'de>,
         {
@@ -278,7 +580,7 @@ mod serde_impl {
     }
 
     impl Serialize for Numeric {
-        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
         where
             S: Serializer,
         {
@@ -291,7 +593,7 @@ mod serde_impl {
     }
 
     impl<'de> Deserialize<'de> for Numeric {
-        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
         where
             D: Deserializer<'de>,
         {
@@ -305,3 +607,78 @@ mod serde_impl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_operators() {
+        let a = Numeric::new(150, 100);
+        let b = Numeric::new(25, 100);
+        assert!((a + b).equal(Numeric::new(175, 100)));
+        assert!((a - b).equal(Numeric::new(125, 100)));
+        assert!((a * b).equal(Numeric::new(375, 1000)));
+        assert!((a / b).equal(Numeric::new(6, 1)));
+    }
+
+    #[test]
+    fn test_numeric_checked_rounding() {
+        let a = Numeric::new(1, 3);
+        let rounded = a.checked_add(Numeric::zero(), 100, RoundingMode::Round).unwrap();
+        assert_eq!(rounded, Numeric::new(33, 100));
+        assert!(a.checked_add(Numeric::zero(), 100, RoundingMode::Never).is_err());
+    }
+
+    #[test]
+    fn test_numeric_parse_decimal() {
+        let n: Numeric = "1,234.56".parse().unwrap();
+        assert!(n.equal(Numeric::new(123456, 100)));
+        assert!(Numeric::parse_decimal("-12.5").unwrap().equal(Numeric::new(-125, 10)));
+        assert!(Numeric::parse_decimal("12a").is_err());
+        assert!(Numeric::parse_decimal("").is_err());
+    }
+
+    #[test]
+    fn test_numeric_format() {
+        let n = Numeric::new(-123456789, 100);
+        assert_eq!(n.to_decimal_string(100).unwrap(), "-1234567.89");
+        assert_eq!(n.to_decimal_string(1).unwrap(), "-1234568");
+        let info = PrintInfo {
+            thousands_sep: Some('.'),
+            decimal_point: ',',
+            ..PrintInfo::new(100)
+        };
+        assert_eq!(n.format_with(&info).unwrap(), "-1.234.567,89");
+        assert_eq!(Numeric::parse_with("-1.234.567,89", &info).unwrap(), n);
+    }
+
+    #[test]
+    fn test_decimal_places() {
+        assert_eq!(decimal_places(1), 0);
+        assert_eq!(decimal_places(100), 2);
+        assert_eq!(decimal_places(1000000), 6);
+    }
+
+    #[test]
+    fn test_numeric_div_by_zero() {
+        let result =
+            Numeric::new(1, 1).checked_div(Numeric::zero(), DENOM_AUTO, RoundingMode::Never);
+        assert!(matches!(result, Err(Error::Numeric(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let n = Numeric::new(-12345, 100);
+        let json = serde_json::to_string(&n).unwrap();
+        assert_eq!(json, r#"{"num":-12345,"denom":100}"#);
+        assert_eq!(serde_json::from_str::<Numeric>(&json).unwrap(), n);
+
+        let guid = Guid::parse("0123456789abcdef0123456789abcdef").unwrap();
+        let json = serde_json::to_string(&guid).unwrap();
+        assert_eq!(json, r#""0123456789abcdef0123456789abcdef""#);
+        assert_eq!(serde_json::from_str::<Guid>(&json).unwrap(), guid);
+        assert!(serde_json::from_str::<Guid>(r#""not a guid""#).is_err());
+    }
+}